@@ -1,24 +1,41 @@
 pub mod maze {
-    use std::ops::ControlFlow;
-    use std::collections::{HashSet, VecDeque};
+    use std::cmp::Reverse;
+    use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+    use std::panic::AssertUnwindSafe;
+    use std::sync::mpsc::{Receiver, SyncSender};
+    use std::sync::Arc;
+    use std::thread::JoinHandle;
     // use rand::prelude::SliceRandom;
     use rand::seq::SliceRandom;
     // 0.7.2
-    use rand::Rng;
+    use rand::{Rng, RngCore, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use rayon::prelude::*;
 
     pub type Coord = (usize, usize);
 
-    #[derive(Debug, Clone)]
+    // the rng every seeded generation goes through. ChaCha8 output is fixed for a given seed and does not change
+    // between versions, so a seed is enough to recreate a maze.
+    pub type MazeRng = ChaCha8Rng;
+
+    pub fn rng_from_seed(seed: u64) -> MazeRng {
+        MazeRng::seed_from_u64(seed)
+    }
+
+    // the wall on the west side of the cell at `coord`, i.e. the one separating it from its west neighbour.
+    // It runs vertically on screen; the name comes from it being indexed along a row.
+    #[derive(Debug, Clone, PartialEq)]
     pub struct HorizontalWall {
         coord: Coord,
     }
 
-    #[derive(Debug, Clone)]
+    // the wall on the north side of the cell at `coord`, separating it from its north neighbour.
+    #[derive(Debug, Clone, PartialEq)]
     pub struct VerticalWall {
         coord: Coord,
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone, PartialEq)]
     pub enum Wall {
         VerticalWall(VerticalWall),
         HorizontalWall(HorizontalWall),
@@ -38,6 +55,296 @@ pub mod maze {
         }
     }
 
+    impl Direction {
+        pub fn opposite(&self) -> Direction {
+            match self {
+                Direction::North => Direction::South,
+                Direction::South => Direction::North,
+                Direction::East => Direction::West,
+                Direction::West => Direction::East,
+            }
+        }
+
+        // a quarter turn to the right.
+        pub fn clockwise(&self) -> Direction {
+            match self {
+                Direction::North => Direction::East,
+                Direction::East => Direction::South,
+                Direction::South => Direction::West,
+                Direction::West => Direction::North,
+            }
+        }
+
+        // a quarter turn to the left.
+        pub fn counter_clockwise(&self) -> Direction {
+            self.clockwise().opposite()
+        }
+    }
+
+    const DIRECTIONS: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+
+    // the corner every passage of a binary tree maze leads towards.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum BinaryBias {
+        NorthEast,
+        NorthWest,
+        SouthEast,
+        SouthWest,
+    }
+
+    impl BinaryBias {
+        fn directions(&self) -> [Direction; 2] {
+            match self {
+                BinaryBias::NorthEast => [Direction::North, Direction::East],
+                BinaryBias::NorthWest => [Direction::North, Direction::West],
+                BinaryBias::SouthEast => [Direction::South, Direction::East],
+                BinaryBias::SouthWest => [Direction::South, Direction::West],
+            }
+        }
+    }
+
+    // which active cell the growing tree generator continues from.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum CellSelection {
+        // behaves like the DFS backtracker.
+        Newest,
+        Oldest,
+        // behaves like Prim's.
+        Random,
+        Middle,
+        // picks one of the strategies with probability proportional to its weight,
+        // e.g. `Mix(vec![(CellSelection::Newest, 3), (CellSelection::Random, 1)])` for 75% newest, 25% random.
+        Mix(Vec<(CellSelection, u32)>),
+    }
+
+    impl CellSelection {
+        fn pick<R: Rng + ?Sized>(&self, len: usize, rng: &mut R) -> usize {
+            match self {
+                CellSelection::Newest => len - 1,
+                CellSelection::Oldest => 0,
+                CellSelection::Random => rng.gen_range(0..len),
+                CellSelection::Middle => len / 2,
+                CellSelection::Mix(strategies) => match strategies.choose_weighted(rng, |(_, weight)| *weight) {
+                    Ok((strategy, _)) => strategy.pick(len, rng),
+                    // an empty mix or one with only zero weights
+                    Err(_) => CellSelection::Newest.pick(len, rng),
+                },
+            }
+        }
+    }
+
+    // a route through the maze: `cells` from the first cell to the last, and `directions[i]` leads from `cells[i]`
+    // to `cells[i + 1]`, so there is one direction less than there are cells.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Path {
+        pub cells: Vec<Coord>,
+        pub directions: Vec<Direction>,
+    }
+
+    // the distance from one cell to every cell reachable from it, 4 bytes per cell.
+    #[derive(Debug, Clone, PartialEq)]
+    struct Distances {
+        width: usize,
+        height: usize,
+        // row by row, `NO_COST` for cells that were not reached.
+        values: Vec<u32>,
+    }
+
+    impl Distances {
+        fn unreached(width: usize, height: usize) -> Self {
+            Distances { width, height, values: vec![NO_COST; width * height] }
+        }
+
+        // None for cells that cannot be reached and cells outside the grid.
+        fn get(&self, (row, col): Coord) -> Option<usize> {
+            if row < self.height && col < self.width {
+                match self.values[row * self.width + col] {
+                    NO_COST => None,
+                    distance => Some(distance as usize),
+                }
+            } else {
+                None
+            }
+        }
+    }
+
+    // estimate of the remaining distance from a cell to the goal, used by `Maze::solve_astar`. The path is only
+    // guaranteed to be shortest if the estimate never exceeds the real distance.
+    pub enum Heuristic {
+        // rows plus columns between the two cells.
+        Manhattan,
+        // no estimate at all, which turns A* into Dijkstra's algorithm.
+        Zero,
+        // called with the cell and the goal.
+        Custom(Box<dyn Fn(Coord, Coord) -> usize>),
+    }
+
+    impl Heuristic {
+        fn estimate(&self, cell: Coord, goal: Coord) -> usize {
+            match self {
+                Heuristic::Manhattan => cell.0.abs_diff(goal.0) + cell.1.abs_diff(goal.1),
+                Heuristic::Zero => 0,
+                Heuristic::Custom(estimate) => estimate(cell, goal),
+            }
+        }
+    }
+
+    // what `Maze::solve_astar` found, and how many cells it expanded to find it.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct SearchResult {
+        pub path: Option<Path>,
+        pub expanded: usize,
+    }
+
+    // what `Maze::fill_dead_ends` leaves open and the order it sealed the rest in.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct DeadEndFill {
+        // the cells that were never sealed, row by row. In a perfect maze this is exactly the path from `start` to
+        // `end`; loops survive the filling, so a braided maze can leave more.
+        pub solution: Vec<Coord>,
+        pub fill_order: Vec<Coord>,
+    }
+
+    // which wall a wall follower keeps its hand on.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum Hand {
+        Left,
+        Right,
+    }
+
+    // the walk of `Maze::follow_wall`.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct WallFollow {
+        // every cell entered in order, starting with the first one. Dead ends show up as the walker turning around
+        // and walking back through the same cells.
+        pub trace: Vec<Coord>,
+        // false if the walker ended up circling an island and would never reach the goal.
+        pub reached: bool,
+    }
+
+    // the walk of `Maze::solve_tremaux`.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct TremauxWalk {
+        // every cell entered in order, starting with the first one.
+        pub trace: Vec<Coord>,
+        // how often each passage was walked, keyed like `Maze::passages` and in the same order. Once the goal is
+        // reached, the passages marked once are the path to it.
+        pub marks: Vec<((Coord, Coord), u8)>,
+        // false if every reachable passage was walked twice without finding the goal.
+        pub reached: bool,
+    }
+
+    // the walk of `Maze::solve_pledge`.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct PledgeWalk {
+        // every cell entered in order, starting with the first one.
+        pub trace: Vec<Coord>,
+        // the turn counter after each step of `trace`, +1 per quarter turn to the right and -1 to the left.
+        pub turns: Vec<i32>,
+        // false if the walker was walled in or started going round in circles.
+        pub reached: bool,
+    }
+
+    // one step of a generator, see `Maze::generation_steps`.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum GenerationEvent {
+        // a cell became part of the maze.
+        Visit(Coord),
+        // the wall on the `Direction` side of the cell was knocked down.
+        Carve(Coord, Direction),
+        // the wall on the `Direction` side of the cell was put up, only recursive division does this.
+        Build(Coord, Direction),
+        // the generator gave up on a cell that had nowhere left to go.
+        Backtrack(Coord),
+        Done,
+    }
+
+    // how far the worker thread of `Maze::generation_steps` may run ahead of whoever reads the events.
+    const STEP_BUFFER: usize = 1024;
+
+    // where the generators report their steps while `Maze::generation_steps` runs them, nowhere otherwise. It is
+    // not part of the maze, so `PartialEq for Maze` skips it and a clone does not report to it.
+    #[derive(Debug, Default)]
+    struct Recorder(Option<SyncSender<GenerationEvent>>);
+
+    impl Clone for Recorder {
+        fn clone(&self) -> Self {
+            Recorder(None)
+        }
+    }
+
+    // what `Maze::record` unwinds with once the `generation_steps` iterator is dropped, so the worker stops.
+    struct Abandoned;
+
+    // the events of `Maze::generation_steps`, in the order the worker thread sends them.
+    struct GenerationSteps {
+        events: Receiver<GenerationEvent>,
+        worker: Option<JoinHandle<()>>,
+    }
+
+    impl Iterator for GenerationSteps {
+        type Item = GenerationEvent;
+
+        fn next(&mut self) -> Option<GenerationEvent> {
+            match self.events.recv() {
+                Ok(event) => Some(event),
+                Err(_) => {
+                    // the worker is done; pass its panic on instead of ending quietly.
+                    if let Some(Err(panic)) = self.worker.take().map(|worker| worker.join()) {
+                        std::panic::resume_unwind(panic);
+                    }
+                    None
+                }
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum MazeError {
+        // zero, or too large to address every cell.
+        InvalidDimensions { width: usize, height: usize },
+        StartOutOfBounds { start: Coord, width: usize, height: usize },
+        CellOutOfBounds { coord: Coord, width: usize, height: usize },
+        WallOutOfBounds(Wall),
+        // the generator ran out of steps before every cell was visited.
+        StepBudgetExhausted { steps: usize, visited: usize, total: usize },
+        // a character other than '#' or '.', or a row of a different length, in a text mask.
+        InvalidMask { row: usize, col: usize },
+        MaskSizeMismatch { mask: (usize, usize), maze: (usize, usize) },
+        // the enabled cells of a mask are empty or fall apart into separate regions.
+        DisconnectedMask,
+        MaskedCell(Coord),
+        // the generator relies on the full rectangle and cannot work around masked cells.
+        MaskNotSupported(String),
+    }
+
+    impl std::fmt::Display for MazeError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                MazeError::InvalidDimensions { width, height } => write!(f, "illegal dimensions {}x{}", width, height),
+                MazeError::StartOutOfBounds { start, width, height } => {
+                    write!(f, "start {:?} is outside the {}x{} grid", start, width, height)
+                }
+                MazeError::CellOutOfBounds { coord, width, height } => {
+                    write!(f, "cell {:?} is outside the {}x{} grid", coord, width, height)
+                }
+                MazeError::WallOutOfBounds(wall) => write!(f, "{:?} is outside the grid", wall),
+                MazeError::StepBudgetExhausted { steps, visited, total } => {
+                    write!(f, "step budget of {} exhausted after visiting {} of {} cells", steps, visited, total)
+                }
+                MazeError::InvalidMask { row, col } => write!(f, "invalid mask cell at row {}, column {}", row, col),
+                MazeError::MaskSizeMismatch { mask, maze } => {
+                    write!(f, "{}x{} mask does not fit a {}x{} maze", mask.0, mask.1, maze.0, maze.1)
+                }
+                MazeError::DisconnectedMask => write!(f, "the enabled cells of the mask are not connected"),
+                MazeError::MaskedCell(coord) => write!(f, "cell {:?} is masked out", coord),
+                MazeError::MaskNotSupported(name) => write!(f, "the {} generator does not support masks", name),
+            }
+        }
+    }
+
+    impl std::error::Error for MazeError {}
+
     #[derive(Debug)]
     pub struct MazeCell {
         cost: Option<usize>,
@@ -48,24 +355,306 @@ pub mod maze {
         right: HorizontalWall,
     }
 
+    // `Distances` value of a cell that has not been reached.
+    const NO_COST: u32 = u32::MAX;
+
+    // which cells of the grid take part in the maze, for mazes that are not rectangular. Masked out cells keep all
+    // their walls and are skipped by the generators and the distance calculations. See `Maze::set_mask`.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Mask {
+        width: usize,
+        height: usize,
+        enabled: Bits,
+    }
+
+    impl Mask {
+        // every cell enabled.
+        pub fn new(width: usize, height: usize) -> Self {
+            Mask { width, height, enabled: Bits::new(width * height, true) }
+        }
+
+        // one line per row, '.' for a cell in the maze and '#' for a masked out one. Whitespace around the rows
+        // and blank lines are ignored, so the text can be indented and start and end with a newline.
+        pub fn from_text(text: &str) -> Result<Self, MazeError> {
+            let rows: Vec<&str> = text.lines().map(|line| line.trim()).filter(|line| !line.is_empty()).collect();
+            let width = rows.first().map_or(0, |row| row.chars().count());
+            let mut result = Mask::new(width, rows.len());
+            for (row, line) in rows.iter().enumerate() {
+                let mut length = 0;
+                for (col, cell) in line.chars().enumerate() {
+                    match cell {
+                        '.' if col < width => {}
+                        '#' if col < width => result.enabled.set(row * width + col, false),
+                        _ => return Err(MazeError::InvalidMask { row, col }),
+                    }
+                    length += 1;
+                }
+                if length != width {
+                    return Err(MazeError::InvalidMask { row, col: length });
+                }
+            }
+            Ok(result)
+        }
+
+        pub fn width(&self) -> usize {
+            self.width
+        }
+
+        pub fn height(&self) -> usize {
+            self.height
+        }
+
+        // false for masked out cells and cells outside the grid.
+        pub fn is_enabled(&self, (row, col): Coord) -> bool {
+            row < self.height && col < self.width && self.enabled.get(row * self.width + col)
+        }
+
+        pub fn set(&mut self, (row, col): Coord, enabled: bool) -> Result<(), MazeError> {
+            if row >= self.height || col >= self.width {
+                return Err(MazeError::CellOutOfBounds { coord: (row, col), width: self.width, height: self.height });
+            }
+            self.enabled.set(row * self.width + col, enabled);
+            Ok(())
+        }
+
+        pub fn enabled_count(&self) -> usize {
+            self.enabled.words.iter().map(|word| word.count_ones() as usize).sum()
+        }
+
+        // true if there is at least one enabled cell and all of them can reach each other.
+        fn is_connected(&self) -> bool {
+            let first = match (0..self.width * self.height).find(|index| self.enabled.get(*index)) {
+                Some(index) => (index / self.width, index % self.width),
+                None => return false,
+            };
+            let mut seen = Bits::new(self.width * self.height, false);
+            seen.set(first.0 * self.width + first.1, true);
+            let mut stack = vec![first];
+            let mut count = 1;
+            while let Some(coord) = stack.pop() {
+                for direction in DIRECTIONS.iter() {
+                    if let Some((row, col)) = Maze::neighbor_coord(coord, direction, self.width, self.height) {
+                        if self.enabled.get(row * self.width + col) && !seen.get(row * self.width + col) {
+                            seen.set(row * self.width + col, true);
+                            stack.push((row, col));
+                            count += 1;
+                        }
+                    }
+                }
+            }
+            count == self.enabled_count()
+        }
+    }
+
+    // walls are stored one bit per edge, including the outer border:
+    // `horizontal_walls` holds the walls west of every cell plus the east border, `height` rows of `width + 1`,
+    // `vertical_walls` holds the walls north of every cell plus the south border, `height + 1` rows of `width`.
+    // That is about 2 bits per cell, and the distances from `start` add a u32 per cell: about 4.25 bytes per cell
+    // in all, against about 18 for the `Vec<Vec<bool>>` walls and `Option<usize>` costs this replaced.
+    #[derive(Debug, Clone)]
     pub struct Maze {
-        pub horizontal_walls: Vec<Vec<bool>>,
-        pub vertical_walls: Vec<Vec<bool>>,
-        pub start: Coord,
-        pub end: Coord,
-        pub cost: Vec<Vec<Option<usize>>>,
+        width: usize,
+        height: usize,
+        horizontal_walls: Bits,
+        vertical_walls: Bits,
+        start: Coord,
+        end: Coord,
+        // distance from `start` per cell, see `fill_cost`.
+        cost: Distances,
+        // distance from `start` to `end`, None until the first `fill_cost`.
+        max_cost: Option<usize>,
+        seed: Option<u64>,
+        recorder: Recorder,
+        mask: Option<Mask>,
+    }
+
+    // every field but `recorder`, which only says where the steps go while the maze is generated.
+    impl PartialEq for Maze {
+        fn eq(&self, other: &Self) -> bool {
+            self.width == other.width
+                && self.height == other.height
+                && self.horizontal_walls == other.horizontal_walls
+                && self.vertical_walls == other.vertical_walls
+                && self.start == other.start
+                && self.end == other.end
+                && self.cost == other.cost
+                && self.max_cost == other.max_cost
+                && self.seed == other.seed
+                && self.mask == other.mask
+        }
     }
 
     impl Maze {
+        pub fn width(&self) -> usize {
+            self.width
+        }
+
+        pub fn height(&self) -> usize {
+            self.height
+        }
+
+        pub fn start(&self) -> Coord {
+            self.start
+        }
+
+        // moves `start` and recomputes `end` and `cost` from it.
+        pub fn set_start(&mut self, start: Coord) -> Result<(), MazeError> {
+            self.check_cell(start).map_err(|error| match error {
+                MazeError::CellOutOfBounds { .. } => MazeError::StartOutOfBounds { start, width: self.width, height: self.height },
+                error => error,
+            })?;
+            self.start = start;
+            self.refresh_end_and_cost();
+            Ok(())
+        }
+
+        // the cell farthest from `start`.
+        pub fn end(&self) -> Coord {
+            self.end
+        }
+
+        // the seed the maze was generated from, if it came from `generate_with`/`generate_with_seed`.
+        pub fn seed(&self) -> Option<u64> {
+            self.seed
+        }
+
+        // the distance from `start` as of the last `fill_cost`, None for cells that cannot be reached and cells
+        // outside the grid.
+        pub fn cost(&self, coord: Coord) -> Option<usize> {
+            self.cost.get(coord)
+        }
+
+        // the largest `cost`, None until the first `fill_cost`.
+        pub fn max_cost(&self) -> Option<usize> {
+            self.max_cost
+        }
+
+        fn distances_from(&self, from: Coord) -> Distances {
+            let width = self.width;
+            let mut distances = Distances::unreached(width, self.height);
+            self.breadth_first(from, |(row, col), distance| distances.values[row * width + col] = distance as u32);
+            distances
+        }
+
+        // the wall on the west side of (row, col); `col == width` is the east border.
+        pub fn horizontal_wall(&self, row: usize, col: usize) -> bool {
+            row < self.height && col <= self.width && self.horizontal_walls.get(row * (self.width + 1) + col)
+        }
+
+        // the wall on the north side of (row, col); `row == height` is the south border.
+        pub fn vertical_wall(&self, row: usize, col: usize) -> bool {
+            row <= self.height && col < self.width && self.vertical_walls.get(row * self.width + col)
+        }
+
+        // true if `a` and `b` are adjacent cells with no wall between them.
+        pub fn has_passage(&self, a: Coord, b: Coord) -> bool {
+            DIRECTIONS.iter().any(|direction| {
+                Self::neighbor_coord(a, direction, self.width, self.height) == Some(b) && self.is_open(a, direction)
+            })
+        }
+
+        // the directions you can move in from `coord`, in north, east, south, west order.
+        pub fn open_directions(&self, coord: Coord) -> Vec<Direction> {
+            DIRECTIONS.iter().filter(|direction| self.is_open(coord, direction)).cloned().collect()
+        }
+
+        // the cells you can move to from `coord`, in the same order as `open_directions`.
+        pub fn neighbors(&self, coord: Coord) -> Vec<Coord> {
+            DIRECTIONS.iter()
+                .filter(|direction| self.is_open(coord, direction))
+                .filter_map(|direction| Self::neighbor_coord(coord, direction, self.width, self.height))
+                .collect()
+        }
+
+        // every cell that is not masked out, row by row.
+        pub fn cells(&self) -> impl Iterator<Item = Coord> + '_ {
+            let width = self.width;
+            (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col))).filter(|coord| self.is_enabled(*coord))
+        }
+
+        pub fn mask(&self) -> Option<&Mask> {
+            self.mask.as_ref()
+        }
+
+        // restricts the maze to the enabled cells of `mask`, which has to match the maze size, be connected and
+        // include `start`. Passages into masked out cells stop counting; generate again to carve the new shape.
+        pub fn set_mask(&mut self, mask: Mask) -> Result<(), MazeError> {
+            if (mask.width, mask.height) != (self.width, self.height) {
+                return Err(MazeError::MaskSizeMismatch { mask: (mask.width, mask.height), maze: (self.width, self.height) });
+            }
+            if !mask.is_connected() {
+                return Err(MazeError::DisconnectedMask);
+            }
+            if !mask.is_enabled(self.start) {
+                return Err(MazeError::MaskedCell(self.start));
+            }
+            self.mask = Some(mask);
+            self.refresh_end_and_cost();
+            Ok(())
+        }
+
+        // false for masked out cells and cells outside the grid.
+        pub fn is_enabled(&self, (row, col): Coord) -> bool {
+            match &self.mask {
+                Some(mask) => mask.is_enabled((row, col)),
+                None => row < self.height && col < self.width,
+            }
+        }
+
+        fn enabled_count(&self) -> usize {
+            match &self.mask {
+                Some(mask) => mask.enabled_count(),
+                None => self.width * self.height,
+            }
+        }
+
+        // a uniformly random cell that is not masked out. Without a mask this draws the row and then the column.
+        fn random_enabled_cell<R: Rng + ?Sized>(&self, rng: &mut R) -> Coord {
+            match &self.mask {
+                Some(_) => self.cells().nth(rng.gen_range(0..self.enabled_count())).unwrap(),
+                None => (rng.gen_range(0..self.height), rng.gen_range(0..self.width)),
+            }
+        }
+
+        // the neighbour of `coord` in `direction`, if it is inside the grid and not masked out.
+        fn neighbor(&self, coord: Coord, direction: &Direction) -> Option<Coord> {
+            Self::neighbor_coord(coord, direction, self.width, self.height).filter(|next| self.is_enabled(*next))
+        }
+
+        // every open passage once, as a cell and its east or south neighbour.
+        pub fn passages(&self) -> impl Iterator<Item = (Coord, Coord)> + '_ {
+            self.cells().flat_map(move |coord| {
+                [Direction::East, Direction::South].into_iter()
+                    .filter(move |direction| self.is_open(coord, direction))
+                    .filter_map(move |direction| Self::neighbor_coord(coord, &direction, self.width, self.height))
+                    .map(move |next| (coord, next))
+            })
+        }
+
+        // whether there is a cell in `direction` from `coord` and no wall in the way.
+        fn is_open(&self, (row, col): Coord, direction: &Direction) -> bool {
+            if !self.is_enabled((row, col)) || self.neighbor((row, col), direction).is_none() {
+                return false;
+            }
+            match direction {
+                Direction::North => !self.vertical_wall(row, col),
+                Direction::South => !self.vertical_wall(row + 1, col),
+                Direction::West => !self.horizontal_wall(row, col),
+                Direction::East => !self.horizontal_wall(row, col + 1),
+            }
+        }
+
+        // None outside the grid and for masked out cells.
         pub fn get_maze_cell(&self, row_index: i32, col_index: i32) -> Option<MazeCell> {
-            if row_index >= self.horizontal_walls.len() as i32 || col_index >= self.vertical_walls.len() as i32 || row_index < 0 || col_index < 0 {
+            if row_index >= self.height as i32 || col_index >= self.width as i32 || row_index < 0 || col_index < 0
+                || !self.is_enabled((row_index as usize, col_index as usize)) {
                 None
             } else {
                 let row_index = row_index as usize;
                 let col_index = col_index as usize;
                 Some(MazeCell {
                     coord: (row_index, col_index),
-                    cost: self.cost[row_index][col_index],
+                    cost: self.cost((row_index, col_index)),
                     top: VerticalWall {
                         coord: (row_index, col_index),
                     },
@@ -115,298 +704,2321 @@ pub mod maze {
             }
         }
 
+        // false for closed walls and walls next to a masked out cell.
         pub fn is_traversable<'a>(&self, wall: &Wall) -> bool {
+            if self.masked_side(wall).is_some() {
+                return false;
+            }
             match wall {
                 Wall::HorizontalWall(HorizontalWall { coord: (row, col) }) => {
-                    if *row >= self.horizontal_walls.len() || *col >= self.horizontal_walls[0].len() {
+                    if *row >= self.height || *col > self.width {
                         false
                     } else {
-                        !self.horizontal_walls[*row][*col]
+                        !self.horizontal_wall(*row, *col)
                     }
                 }
                 Wall::VerticalWall(VerticalWall { coord: (row, col) }) => {
                     // println!("({},{})", row, col);
-                    if *row >= self.vertical_walls.len() || *col >= self.vertical_walls[0].len() {
+                    if *col >= self.width || *row > self.height {
                         false
                     } else {
-                        !self.vertical_walls[*col][*row]
+                        !self.vertical_wall(*row, *col)
                     }
                 }
             }
         }
 
+        // a `None` direction leaves the walls as they are. Fails with `MaskedCell` if the cell or the one across the
+        // wall is masked out.
         pub fn set_wall_by_cell(
             &mut self,
             row_cell_index: usize,
             col_cell_index: usize,
             direction: Option<Direction>,
             value: bool,
-        ) {
-            let maze_cell_value = self.get_maze_cell(row_cell_index as i32, col_cell_index as i32);
-            // println!("{:?}", cell_value);
-            match maze_cell_value {
-                Some(maze_cell) => match direction {
-                    Some(Direction::North) => self.set_wall(&Wall::VerticalWall(maze_cell.top), value),
-                    Some(Direction::East) => self.set_wall(&Wall::HorizontalWall(maze_cell.right), value),
-                    Some(Direction::South) => self.set_wall(&Wall::VerticalWall(maze_cell.down), value),
-                    Some(Direction::West) => self.set_wall(&Wall::HorizontalWall(maze_cell.left), value),
-                    None => {}
-                },
-                None => {
-                    // panic!("illegal cell indexes");
+        ) -> Result<(), MazeError> {
+            self.check_cell((row_cell_index, col_cell_index))?;
+            if let Some(direction) = direction {
+                let across = Self::neighbor_coord((row_cell_index, col_cell_index), &direction, self.width, self.height);
+                if let Some(across) = across.filter(|x| !self.is_enabled(*x)) {
+                    return Err(MazeError::MaskedCell(across));
                 }
+                self.set_cell_wall((row_cell_index, col_cell_index), &direction, value);
             }
+            Ok(())
         }
 
-        pub fn set_wall(&mut self, wall: &Wall, value: bool) {
+        // fails with `MaskedCell` for a wall next to a masked out cell.
+        pub fn set_wall(&mut self, wall: &Wall, value: bool) -> Result<(), MazeError> {
+            if let Some(coord) = self.masked_side(wall) {
+                return Err(MazeError::MaskedCell(coord));
+            }
             match wall {
-                Wall::HorizontalWall(HorizontalWall { coord: (row, col) }) => {
-                    self.horizontal_walls[*row][*col] = value;
+                Wall::HorizontalWall(HorizontalWall { coord: (row, col) }) if *row < self.height && *col <= self.width => {
+                    self.horizontal_walls.set(row * (self.width + 1) + col, value);
+                    Ok(())
                 }
-                Wall::VerticalWall(VerticalWall { coord: (row, col) }) => {
-                    self.vertical_walls[*col][*row] = value;
+                Wall::VerticalWall(VerticalWall { coord: (row, col) }) if *row <= self.height && *col < self.width => {
+                    self.vertical_walls.set(row * self.width + col, value);
+                    Ok(())
                 }
+                _ => Err(MazeError::WallOutOfBounds(wall.clone())),
             }
         }
 
-        pub fn new(width: usize, height: usize, start: Coord) -> Self {
-            if width <= 0 || height <= 0 {
-                panic!("illegal dimensions")
-            } else {
-                let mut result = Maze {
-                    start: start.clone(),
-                    end: (0, 0),
-                    horizontal_walls: std::iter::repeat(
-                        std::iter::repeat(true).take(width + 1).collect()
-                    ).take(height).collect(),
-                    vertical_walls: std::iter::repeat(
-                        std::iter::repeat(true).take(height + 1).collect()
-                    )
-                        .take(width)
-                        .collect(),
-                    cost: std::iter::repeat(
-                        std::iter::repeat(None).take(width).collect()
-                    ).take(height).collect(),
-                };
-                // maze.generate_binary_maze();
-                // maze.generate_random_walk_maze();
-                // maze.generate_maze_via_dfs((0, 0), HashSet::new(), width, height);
-                result.generate_maze_via_dfs_heap(start, width, height);
-                let end = result.find_farthest_point(&start, width, height);
-                result.end = end;
-                result.fill_cost();
-                result
+        // the masked out cell on either side of `wall`, if there is one.
+        fn masked_side(&self, wall: &Wall) -> Option<Coord> {
+            let (before, after) = match wall {
+                Wall::HorizontalWall(HorizontalWall { coord: (row, col) }) => (col.checked_sub(1).map(|col| (*row, col)), (*row, *col)),
+                Wall::VerticalWall(VerticalWall { coord: (row, col) }) => (row.checked_sub(1).map(|row| (row, *col)), (*row, *col)),
+            };
+            before.into_iter().chain([after])
+                .find(|(row, col)| *row < self.height && *col < self.width && !self.is_enabled((*row, *col)))
+        }
+
+        // unchecked `set_wall_by_cell` for the generators, which only pass cells inside the grid.
+        fn set_cell_wall(&mut self, (row, col): Coord, direction: &Direction, value: bool) {
+            if self.recorder.0.is_some() {
+                self.record(match value {
+                    true => GenerationEvent::Build((row, col), direction.clone()),
+                    false => GenerationEvent::Carve((row, col), direction.clone()),
+                });
+            }
+            match direction {
+                Direction::North => self.vertical_walls.set(row * self.width + col, value),
+                Direction::South => self.vertical_walls.set((row + 1) * self.width + col, value),
+                Direction::West => self.horizontal_walls.set(row * (self.width + 1) + col, value),
+                Direction::East => self.horizontal_walls.set(row * (self.width + 1) + col + 1, value),
             }
         }
-        pub fn get_valid_adjascent_cells(&self, row: usize, col: usize) -> Vec<MazeCell> {
-            let maze_cells: Vec<Option<MazeCell>> = [Direction::South, Direction::West, Direction::North, Direction::East].iter().map(|d| {
-                match &self.get_maze_cell(row as i32, col as i32) {
-                    None => None,
-                    Some(maze_cell) => {
-                        self.get_cell_by_direction(&maze_cell, d)
-                    }
-                }
-            }).filter(|x| x.is_some()).collect();
-            // println!("{:?}", maze_cells);
-            maze_cells.into_iter()
-                .map(|x| x.unwrap())
-                .filter(|x| x.cost.is_none()).collect::<Vec<MazeCell>>()
+
+        fn check_cell(&self, coord: Coord) -> Result<(), MazeError> {
+            if coord.0 >= self.height || coord.1 >= self.width {
+                Err(MazeError::CellOutOfBounds { coord, width: self.width, height: self.height })
+            } else if !self.is_enabled(coord) {
+                Err(MazeError::MaskedCell(coord))
+            } else {
+                Ok(())
+            }
         }
 
-        pub fn fill_cost(&mut self) {
-            let start = self.start.clone();
-            let mut queue: VecDeque<(Coord, usize)> = VecDeque::new();
-            queue.push_front((start.clone(), 0));
-            while let Some(((row, col), cost)) = queue.pop_back() {
-                self.get_valid_adjascent_cells(row, col).iter()
-                    .for_each(|x| {
-                        self.cost[x.coord.0][x.coord.1] = Some(cost + 1);
-                        queue.push_front((x.coord.clone(), cost + 1));
-                    });
+        // for the generators that need every cell of the rectangle.
+        fn check_unmasked(&self, generator: &str) -> Result<(), MazeError> {
+            match self.mask {
+                Some(_) => Err(MazeError::MaskNotSupported(generator.to_string())),
+                None => Ok(()),
             }
         }
 
-        pub fn generate_binary_maze(&mut self) {
-            fn random_direction(excludes: &[Direction]) -> Option<Direction> {
-                let directions =
-                    [Direction::North, /*Direction::South,*/ Direction::East, /*Direction::West*/];
-                // .filter(|direction| !excludes.contains(direction)).collect();
+        // panics on zero dimensions or a `start` outside the grid, see `try_new`.
+        pub fn new(width: usize, height: usize, start: Coord) -> Self {
+            Self::generate_with(width, height, start, &DfsBacktracker)
+        }
 
-                match directions.choose(&mut rand::thread_rng()) {
-                    Some(x) if !excludes.contains(x) => Some(x.clone()),
-                    Some(_) => None,
-                    None => None
-                }
-            }
+        pub fn try_new(width: usize, height: usize, start: Coord) -> Result<Self, MazeError> {
+            Self::try_generate_with(width, height, start, &DfsBacktracker)
+        }
 
-            fn indexes_to_exclude(row_index: usize, col_index: usize, width: usize, height: usize) -> Vec<Direction> {
-                let mut result = Vec::with_capacity(2);
-                if row_index == 0 {
-                    result.push(Direction::North);
-                }
-                if col_index == 0 {
-                    result.push(Direction::West);
-                }
+        // a binary tree maze where every cell opens towards one of the two directions of `bias`.
+        pub fn new_binary(width: usize, height: usize, start: Coord, bias: BinaryBias) -> Self {
+            Self::generate_with(width, height, start, &BinaryTree { bias })
+        }
 
-                if row_index == height - 1 {
-                    result.push(Direction::South);
-                }
+        // generates with a random seed, which is kept in `seed`.
+        pub fn generate_with(width: usize, height: usize, start: Coord, generator: &dyn MazeGenerator) -> Self {
+            Self::generate_with_seed(width, height, start, generator, rand::thread_rng().gen())
+        }
 
-                if col_index == width - 1 {
-                    result.push(Direction::East);
-                }
-                // println!("{:?}, ({}, {})", result, row_index, col_index);
-                result
-            }
+        // the same seed, generator and size always give the same maze.
+        pub fn generate_with_seed(width: usize, height: usize, start: Coord, generator: &dyn MazeGenerator, seed: u64) -> Self {
+            Self::try_generate_with_seed(width, height, start, generator, seed).unwrap_or_else(|error| panic!("{}", error))
+        }
 
-            let width = self.vertical_walls.len();
-            let height = self.horizontal_walls.len();
-            (0..width).for_each(|cell_row_index| {
-                (0..height).for_each(|cell_col_index| {
-                    self.set_wall_by_cell(cell_row_index, cell_col_index, random_direction(&indexes_to_exclude(cell_row_index, cell_col_index, width, height)), false);
-                    // indexes_to_exclude(cell_row_index, cell_col_index, width, height).iter().for_each(|direction|{
-                    //     self.set_wall_by_cell(width - 1, 1, direction.clone(), false);
-                    // });
-                })
-            })
+        pub fn try_generate_with(width: usize, height: usize, start: Coord, generator: &dyn MazeGenerator) -> Result<Self, MazeError> {
+            Self::try_generate_with_seed(width, height, start, generator, rand::thread_rng().gen())
         }
 
-        fn get_next<'a>(coord: (usize, usize), acc: &HashSet<(usize, usize)>, width: usize, height: usize) -> Vec<((usize, usize), Direction)> {
-            let (row, col) = coord;
-            let result: Vec<((usize, usize), Direction)> =
-                vec![((row as i32 + 1, col as i32), Direction::South), ((row as i32 - 1, col as i32), Direction::North), ((row as i32, col as i32 + 1), Direction::East), ((row as i32, col as i32 - 1), Direction::West)]
-                    .iter()
-                    .filter(|((x, y), _)| {
-                        (*x >= 0 && *y >= 0 && *y < height as i32 && *x < width as i32) && !acc.contains(&(*x as usize, *y as usize))
-                    })
-                    .map(|((x, y), d)| ((*x as usize, *y as usize), d.clone())).collect::<Vec<((usize, usize), Direction)>>();
-            // if row == 0 || col == 0 || row == width - 1 || col == height - 1 {
-            //     println!("{:?} -> {:?}", (row, col), result);
-            // }
-            result
+        pub fn try_generate_with_seed(width: usize, height: usize, start: Coord, generator: &dyn MazeGenerator, seed: u64) -> Result<Self, MazeError> {
+            let mut result = Self::blank(width, height, start)?;
+            result.seed = Some(seed);
+            generator.generate(&mut result, &mut rng_from_seed(seed))?;
+            Ok(result)
         }
 
-        pub fn generate_random_walk_maze(&mut self) {
-            let width = self.vertical_walls.len();
-            let height = self.horizontal_walls.len();
-            let mut rng = rand::thread_rng();
-            let mut start = (rng.gen_range(0..width), rng.gen_range(0..height));
-            let area = width * height;
-            let mut acc: HashSet<(usize, usize)> = HashSet::with_capacity(area);
+        // a maze the size of `mask` covering only its enabled cells, see `set_mask`. Fails with
+        // `MaskNotSupported` for generators that need the whole rectangle.
+        pub fn try_generate_masked(mask: Mask, start: Coord, generator: &dyn MazeGenerator, seed: u64) -> Result<Self, MazeError> {
+            let mut result = Self::blank(mask.width, mask.height, start)?;
+            result.set_mask(mask)?;
+            result.seed = Some(seed);
+            generator.generate(&mut result, &mut rng_from_seed(seed))?;
+            Ok(result)
+        }
 
-            while acc.len().clone() < area {
-                acc.insert(start.clone());
-                match Self::get_next(start, &acc, width, height).choose(&mut rng) {
-                    None => {
-                        (0..width).map(|x| (0..height).map(move |y| (x, y))).flatten().try_for_each(|coord| {
-                            match acc.contains(&coord) {
-                                true => { ControlFlow::Continue(()) }
-                                false => {
-                                    start = coord;
-                                    ControlFlow::Break(())
-                                }
-                            }
-                        });
-                    }
-                    Some(((x, y), direction)) => {
-                        // if *x == 0 {
-                        // println!("{:?}", ((x, y), direction));
-                        let new_direction = match direction {
-                            Direction::North => Direction::South,
-                            Direction::South => Direction::North,
-                            Direction::East => Direction::West,
-                            Direction::West => Direction::East
-                        };
-                        self.set_wall_by_cell(*x, *y, Some(new_direction), false);
-                        // }
-                        start = (*x, *y);
+        // what `try_generate_with_seed` does with the same arguments, one event at a time and ending in `Done`.
+        // Applying the events in order to `Maze::blank` gives the same maze. The generator runs on a worker thread
+        // that blocks once it is `STEP_BUFFER` events ahead, so only those are ever held in memory. If the generator
+        // fails the events end without `Done`; if the iterator is dropped early the generator stops at its next step.
+        pub fn generation_steps(width: usize, height: usize, start: Coord, generator: Arc<dyn MazeGenerator>, seed: u64) -> Result<impl Iterator<Item = GenerationEvent>, MazeError> {
+            let mut maze = Self::blank(width, height, start)?;
+            let (sender, events) = std::sync::mpsc::sync_channel(STEP_BUFFER);
+            maze.recorder = Recorder(Some(sender));
+            let worker = std::thread::spawn(move || {
+                let run = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                    if generator.generate(&mut maze, &mut rng_from_seed(seed)).is_ok() {
+                        maze.record(GenerationEvent::Done);
                     }
+                }));
+                match run {
+                    Err(panic) if !panic.is::<Abandoned>() => std::panic::resume_unwind(panic),
+                    _ => {}
                 }
-            }
+            });
+            Ok(GenerationSteps { events, worker: Some(worker) })
         }
 
-        pub fn find_farthest_point(&self, start: &(usize, usize), width: usize, height: usize) -> (usize, usize) {
-            let mut queue: VecDeque<((usize, usize), usize)> = VecDeque::with_capacity(width * height);
-            let mut acc: HashSet<(usize, usize)> = HashSet::with_capacity(width * height);
-            queue.push_front((start.clone(), 0));
-            acc.insert(start.clone());
-            let mut coord_with_max_distance = (start.clone(), 0);
-            while let Some((coord, cost)) = queue.pop_back() {
-                // println!("{}", queue.len());
-                if coord_with_max_distance.1 < cost {
-                    coord_with_max_distance = (coord, cost);
+        // replays one event from `generation_steps`. `Done` recomputes `end` and `cost`.
+        pub fn apply(&mut self, event: &GenerationEvent) -> Result<(), MazeError> {
+            match event {
+                GenerationEvent::Carve((row, col), direction) => self.set_wall_by_cell(*row, *col, Some(direction.clone()), false),
+                GenerationEvent::Build((row, col), direction) => self.set_wall_by_cell(*row, *col, Some(direction.clone()), true),
+                GenerationEvent::Visit(coord) | GenerationEvent::Backtrack(coord) => self.check_cell(*coord),
+                GenerationEvent::Done => {
+                    self.refresh_end_and_cost();
+                    Ok(())
                 }
-                let other: Vec<Coord> = [Direction::North, Direction::East, Direction::West, Direction::South]
-                    .iter()
-                    .map(|direction| {
-                        match self.get_maze_cell(coord.0 as i32, coord.1 as i32) {
-                            None => None,
-                            Some(maze_cell) => self.get_cell_by_direction(&maze_cell, &direction)
-                        }
-                    }).filter(|x| !x.is_none())
-                    .map(|x| x.unwrap().coord)
-                    .filter(|x| !(&acc).contains(x)).collect();
-                // println!("{:?}", other);
-                other.iter().for_each(|(next_row, next_col)| {
-                    queue.push_front(((*next_row, *next_col), cost + 1));
-                    let _ = &acc.insert((*next_row, *next_col));
-                });
             }
-            coord_with_max_distance.0
         }
 
-        pub fn shuffle<T: Default>(mut collection: Vec<T>) -> Vec<T> {
-            let mut rng = rand::thread_rng();
-            for index in 0..collection.len() {
-                let length = collection.len();
-                let new_range = (index as usize)..length;
-                if !new_range.is_empty() {
-                    collection.swap(index, rng.gen_range(new_range));
+        fn record(&mut self, event: GenerationEvent) {
+            if let Some(sender) = &self.recorder.0 {
+                if sender.send(event).is_err() {
+                    // nobody is reading the events any more; unwind out of the generator, `generation_steps` catches it.
+                    std::panic::resume_unwind(Box::new(Abandoned));
                 }
             }
-            collection
         }
-        pub fn generate_maze_via_dfs_heap(&mut self, mut start: (usize, usize), width: usize, height: usize) {
-            let mut stack: VecDeque<(usize, usize)> = VecDeque::with_capacity(width * height);
-            let mut acc: HashSet<(usize, usize)> = HashSet::with_capacity(width * height);
-            stack.push_back(start.clone());
-            while stack.len() > 0 {
-                start = stack.pop_back().unwrap();
-                acc.insert(start.clone());
-                let adj = Self::get_next(start.clone(), &acc, width, height);
-                if let Some(((next_row, next_col), direction)) = adj.choose(&mut rand::thread_rng()) {
-                    self.set_wall_by_cell(start.0, start.1, Some(direction.clone()), false);
-                    stack.push_back(start.clone());
-                    stack.push_back((*next_row, *next_col));
-                }
+
+        // every wall closed and no costs yet, the starting point for replaying `generation_steps`.
+        pub fn blank(width: usize, height: usize, start: Coord) -> Result<Self, MazeError> {
+            // distances are stored as u32, so every cell has to be addressable by one.
+            let addressable = width.checked_add(1).zip(height.checked_add(1))
+                .and_then(|(x, y)| x.checked_mul(y))
+                .is_some_and(|edges| edges < NO_COST as usize);
+            if width == 0 || height == 0 || !addressable {
+                Err(MazeError::InvalidDimensions { width, height })
+            } else if start.0 >= height || start.1 >= width {
+                Err(MazeError::StartOutOfBounds { start, width, height })
+            } else {
+                Ok(Maze {
+                    width,
+                    height,
+                    horizontal_walls: Bits::new(height * (width + 1), true),
+                    vertical_walls: Bits::new((height + 1) * width, true),
+                    start,
+                    end: (0, 0),
+                    cost: Distances::unreached(width, height),
+                    max_cost: None,
+                    seed: None,
+                    recorder: Recorder::default(),
+                    mask: None,
+                })
             }
         }
 
-        pub fn generate_maze_via_dfs(&mut self, start: (usize, usize), mut acc: HashSet<(usize, usize)>, width: usize, height: usize) -> HashSet<(usize, usize)> {
-            acc.insert(start.clone());
-            let mut other = Self::get_next(start.clone(), &acc, width, height);
-            other = Self::shuffle(other);
+        fn close_all_walls(&mut self) {
+            self.horizontal_walls.fill(true);
+            self.vertical_walls.fill(true);
+        }
 
-            for ((row, col), direction) in other.iter() {
-                if !&acc.contains(&(*row, *col)) {
-                    self.set_wall_by_cell(start.0, start.1, Some(direction.clone()), false);
-                    acc = self.generate_maze_via_dfs((*row, *col), acc, width, height);
-                }
-            }
-            acc
+        // recomputes `end` and `cost` after the walls have been regenerated, keeping `start`.
+        pub fn refresh_end_and_cost(&mut self) {
+            self.end = self.fill_distances().unwrap_or(self.start);
         }
-    }
-}
+
+        // fills `cost` with the distance of every cell from `start`; unreachable cells have no cost.
+        pub fn fill_cost(&mut self) {
+            self.fill_distances();
+        }
+
+        // `fill_cost`, returning the first cell found at the largest distance.
+        fn fill_distances(&mut self) -> Option<Coord> {
+            let width = self.width;
+            let mut cost = std::mem::replace(&mut self.cost, Distances::unreached(0, 0));
+            cost.values.fill(NO_COST);
+            let mut farthest: Option<(Coord, usize)> = None;
+            self.breadth_first(self.start, |(row, col), distance| {
+                cost.values[row * width + col] = distance as u32;
+                if farthest.is_none_or(|(_, max)| max < distance) {
+                    farthest = Some(((row, col), distance));
+                }
+            });
+            self.cost = cost;
+            self.max_cost = farthest.map(|(_, max)| max);
+            farthest.map(|(coord, _)| coord)
+        }
+
+        // visits every cell reachable from `start` in breadth-first order, together with its distance from `start`.
+        fn breadth_first<F: FnMut(Coord, usize)>(&self, start: Coord, mut visit: F) {
+            if self.check_cell(start).is_err() {
+                return;
+            }
+            let width = self.width;
+            let mut seen = Bits::new(width * self.height, false);
+            // cell indexes and distances both fit in a u32 (see `blank`), which halves the queue on huge mazes.
+            let mut queue: VecDeque<(u32, u32)> = VecDeque::new();
+            seen.set(start.0 * width + start.1, true);
+            queue.push_back(((start.0 * width + start.1) as u32, 0));
+            while let Some((index, distance)) = queue.pop_front() {
+                let (row, col) = (index as usize / width, index as usize % width);
+                visit((row, col), distance as usize);
+                for direction in [Direction::North, Direction::East, Direction::West, Direction::South] {
+                    if !self.is_open((row, col), &direction) {
+                        continue;
+                    }
+                    let (next_row, next_col) = Self::neighbor_coord((row, col), &direction, width, self.height).unwrap();
+                    let next = next_row * width + next_col;
+                    if !seen.get(next) {
+                        seen.set(next, true);
+                        queue.push_back((next as u32, distance + 1));
+                    }
+                }
+            }
+        }
+        pub fn get_valid_adjascent_cells(&self, row: usize, col: usize) -> Vec<MazeCell> {
+            let maze_cells: Vec<Option<MazeCell>> = [Direction::South, Direction::West, Direction::North, Direction::East].iter().map(|d| {
+                match &self.get_maze_cell(row as i32, col as i32) {
+                    None => None,
+                    Some(maze_cell) => {
+                        self.get_cell_by_direction(&maze_cell, d)
+                    }
+                }
+            }).filter(|x| x.is_some()).collect();
+            // println!("{:?}", maze_cells);
+            maze_cells.into_iter()
+                .map(|x| x.unwrap())
+                .filter(|x| x.cost.is_none()).collect::<Vec<MazeCell>>()
+        }
+
+        // the shortest path from `start` to `end`, read off `cost`. None only if the walls were changed without
+        // `refresh_end_and_cost` and `end` is no longer reachable.
+        pub fn solve(&self) -> Option<Path> {
+            self.path_back(self.end, &self.cost)
+        }
+
+        // the shortest path from `from` to `to`, or None if there is none. Fails for cells outside the grid or masked out.
+        pub fn solve_between(&self, from: Coord, to: Coord) -> Result<Option<Path>, MazeError> {
+            self.check_cell(from)?;
+            self.check_cell(to)?;
+            Ok(self.path_back(to, &self.distances_from(from)))
+        }
+
+        // A* search from `from` to `to` guided by `heuristic`. Ties between equally promising cells go to the one
+        // furthest from `from`, which keeps the expansion count down on open areas.
+        pub fn solve_astar(&self, from: Coord, to: Coord, heuristic: &Heuristic) -> Result<SearchResult, MazeError> {
+            self.check_cell(from)?;
+            self.check_cell(to)?;
+            let width = self.width;
+            let index = |(row, col): Coord| row * width + col;
+            // best known distance from `from`, and the direction each cell was entered through on that route.
+            let mut distances = vec![NO_COST; width * self.height];
+            let mut entered: Vec<Option<Direction>> = vec![None; width * self.height];
+            let mut open = BinaryHeap::new();
+            distances[index(from)] = 0;
+            open.push(Reverse((heuristic.estimate(from, to), Reverse(0), from)));
+            let mut expanded = 0;
+            while let Some(Reverse((_, Reverse(distance), current))) = open.pop() {
+                if distance > distances[index(current)] {
+                    // a better route to `current` was queued after this one.
+                    continue;
+                }
+                expanded += 1;
+                if current == to {
+                    break;
+                }
+                for direction in DIRECTIONS.iter().filter(|direction| self.is_open(current, direction)) {
+                    let next = self.neighbor(current, direction).unwrap();
+                    if distance + 1 < distances[index(next)] {
+                        distances[index(next)] = distance + 1;
+                        entered[index(next)] = Some(direction.clone());
+                        open.push(Reverse(((distance as usize + 1).saturating_add(heuristic.estimate(next, to)), Reverse(distance + 1), next)));
+                    }
+                }
+            }
+            if distances[index(to)] == NO_COST {
+                return Ok(SearchResult { path: None, expanded });
+            }
+            let mut cells = vec![to];
+            let mut directions = Vec::new();
+            while let Some(direction) = &entered[index(*cells.last().unwrap())] {
+                let previous = self.neighbor(*cells.last().unwrap(), &direction.opposite()).unwrap();
+                directions.push(direction.clone());
+                cells.push(previous);
+            }
+            cells.reverse();
+            directions.reverse();
+            Ok(SearchResult { path: Some(Path { cells, directions }), expanded })
+        }
+
+        // dead-end filling: seals every cell other than `start` and `end` with at most one open side, which can turn
+        // its neighbour into a new dead end, until none are left. Only looks at the walls, not at `cost`.
+        pub fn fill_dead_ends(&self) -> DeadEndFill {
+            let width = self.width;
+            let keep = |coord: Coord| coord == self.start || coord == self.end;
+            // open sides per cell that still lead to an unsealed neighbour.
+            let mut open_sides: Vec<u8> = vec![0; width * self.height];
+            let mut sealed = Bits::new(width * self.height, false);
+            let mut queue = VecDeque::new();
+            for coord in self.cells() {
+                open_sides[coord.0 * width + coord.1] = self.open_directions(coord).len() as u8;
+                if open_sides[coord.0 * width + coord.1] <= 1 && !keep(coord) {
+                    sealed.set(coord.0 * width + coord.1, true);
+                    queue.push_back(coord);
+                }
+            }
+            let mut fill_order = Vec::new();
+            while let Some(coord) = queue.pop_front() {
+                fill_order.push(coord);
+                for next in self.neighbors(coord) {
+                    let index = next.0 * width + next.1;
+                    if sealed.get(index) {
+                        continue;
+                    }
+                    open_sides[index] -= 1;
+                    if open_sides[index] <= 1 && !keep(next) {
+                        sealed.set(index, true);
+                        queue.push_back(next);
+                    }
+                }
+            }
+            let solution = self.cells().filter(|(row, col)| !sealed.get(row * width + col)).collect();
+            DeadEndFill { solution, fill_order }
+        }
+
+        // the wall follower: starting at `from` facing north, at every cell turn towards `hand` if that side is open,
+        // otherwise go straight, otherwise turn away from it, and turn around only in a dead end. Reaches `to`
+        // whenever `to` and `from` are both along the same connected wall, which always holds in a perfect maze.
+        // Arriving at a cell facing the same way twice means the walk has become a loop, so it stops there.
+        pub fn follow_wall(&self, from: Coord, to: Coord, hand: Hand) -> Result<WallFollow, MazeError> {
+            self.check_cell(from)?;
+            self.check_cell(to)?;
+            let width = self.width;
+            let facing_index = |direction: &Direction| DIRECTIONS.iter().position(|x| x == direction).unwrap();
+            let mut seen = Bits::new(4 * width * self.height, false);
+            let mut cell = self.get_maze_cell(from.0 as i32, from.1 as i32).unwrap();
+            let mut facing = Direction::North;
+            let mut trace = vec![from];
+            while cell.coord != to {
+                let state = 4 * (cell.coord.0 * width + cell.coord.1) + facing_index(&facing);
+                if seen.get(state) {
+                    return Ok(WallFollow { trace, reached: false });
+                }
+                seen.set(state, true);
+                let turns = match hand {
+                    Hand::Left => [facing.counter_clockwise(), facing.clone(), facing.clockwise(), facing.opposite()],
+                    Hand::Right => [facing.clockwise(), facing.clone(), facing.counter_clockwise(), facing.opposite()],
+                };
+                let next = turns.into_iter()
+                    .find_map(|direction| self.get_cell_by_direction(&cell, &direction).map(|next| (next, direction)));
+                match next {
+                    Some((next, direction)) => {
+                        trace.push(next.coord);
+                        cell = next;
+                        facing = direction;
+                    }
+                    // walled in on every side.
+                    None => return Ok(WallFollow { trace, reached: false }),
+                }
+            }
+            Ok(WallFollow { trace, reached: true })
+        }
+
+        // Trémaux's algorithm, using only what can be seen and marked from inside the maze. Every passage gets a
+        // mark each time it is walked. Arriving at a cell seen before through a fresh passage turns the walker
+        // back; otherwise it takes an unmarked passage if there is one, else one marked once, never one marked
+        // twice. Ties go in north, east, south, west order.
+        pub fn solve_tremaux(&self, from: Coord, to: Coord) -> Result<TremauxWalk, MazeError> {
+            self.check_cell(from)?;
+            self.check_cell(to)?;
+            let key = |a: Coord, b: Coord| if a < b { (a, b) } else { (b, a) };
+            let mut marks: HashMap<(Coord, Coord), u8> = HashMap::new();
+            let mut current = from;
+            let mut arrived_by: Option<Direction> = None;
+            let mut trace = vec![from];
+            while current != to {
+                let exits: Vec<(Direction, Coord)> = DIRECTIONS.iter()
+                    .filter(|direction| self.is_open(current, direction))
+                    .map(|direction| (direction.clone(), self.neighbor(current, direction).unwrap()))
+                    .collect();
+                let mark = |next: Coord| marks.get(&key(current, next)).cloned().unwrap_or(0);
+                let back = arrived_by.as_ref().map(|direction| direction.opposite());
+                let seen_before = exits.iter().any(|(direction, next)| Some(direction) != back.as_ref() && mark(*next) > 0);
+                let choice = match &back {
+                    Some(back) if seen_before && mark(self.neighbor(current, back).unwrap()) == 1 => {
+                        exits.iter().find(|(direction, _)| direction == back)
+                    }
+                    _ => exits.iter().filter(|(_, next)| mark(*next) < 2).min_by_key(|(_, next)| mark(*next)),
+                };
+                let (direction, next) = match choice {
+                    Some(choice) => choice.clone(),
+                    None => break,
+                };
+                *marks.entry(key(current, next)).or_insert(0) += 1;
+                trace.push(next);
+                current = next;
+                arrived_by = Some(direction);
+            }
+            let mut marks: Vec<((Coord, Coord), u8)> = marks.into_iter().collect();
+            marks.sort();
+            Ok(TremauxWalk { trace, marks, reached: current == to })
+        }
+
+        // the Pledge algorithm: head towards `heading` until blocked, then follow the wall on the `hand` side while
+        // counting turns, and leave the wall again once the counter is back at zero, i.e. facing `heading`. It is
+        // made for walking out of a maze and is not guaranteed to reach a goal inside it. Gives up when walled in,
+        // when it comes back to a cell in the same state, or when the counter shows it circled every cell
+        // already, which is how circling an island shows up.
+        pub fn solve_pledge(&self, from: Coord, to: Coord, heading: Direction, hand: Hand) -> Result<PledgeWalk, MazeError> {
+            self.check_cell(from)?;
+            self.check_cell(to)?;
+            let limit = 4 * self.enabled_count() as i32;
+            // quarter turns towards the hand side and away from it.
+            let (toward_hand, away_from_hand) = match hand {
+                Hand::Left => (-1, 1),
+                Hand::Right => (1, -1),
+            };
+            let rotate = |direction: &Direction, quarter_turns: i32| {
+                (0..quarter_turns.rem_euclid(4)).fold(direction.clone(), |direction, _| direction.clockwise())
+            };
+            let mut seen: HashSet<(Coord, usize, i32)> = HashSet::new();
+            let mut current = from;
+            let mut facing = heading.clone();
+            let mut counter: i32 = 0;
+            let mut trace = vec![from];
+            let mut turns = vec![0];
+            while current != to {
+                let facing_index = DIRECTIONS.iter().position(|x| *x == facing).unwrap();
+                if counter.abs() > limit || !seen.insert((current, facing_index, counter)) {
+                    break;
+                }
+                // with the counter at zero the walker faces `heading` and goes straight on if it can; otherwise
+                // it follows the wall, trying the hand side first.
+                let options = if counter == 0 {
+                    [0, away_from_hand, 2 * away_from_hand, 3 * away_from_hand]
+                } else {
+                    [toward_hand, 0, away_from_hand, 2 * away_from_hand]
+                };
+                let step = options.iter().find(|quarter_turns| self.is_open(current, &rotate(&facing, **quarter_turns)));
+                let quarter_turns = match step {
+                    Some(quarter_turns) => *quarter_turns,
+                    None => break,
+                };
+                facing = rotate(&facing, quarter_turns);
+                counter += quarter_turns;
+                current = self.neighbor(current, &facing).unwrap();
+                trace.push(current);
+                turns.push(counter);
+            }
+            Ok(PledgeWalk { trace, turns, reached: current == to })
+        }
+
+        // walks from `to` back to the cell at distance 0 in `distances`, always stepping to an open neighbour one
+        // closer, and returns the walk in forward order.
+        fn path_back(&self, to: Coord, distances: &Distances) -> Option<Path> {
+            if !self.is_enabled(to) {
+                return None;
+            }
+            let mut cells = vec![to];
+            let mut directions = Vec::new();
+            let mut current = to;
+            let mut distance = distances.get(to)?;
+            while distance > 0 {
+                let direction = DIRECTIONS.iter().find(|direction| {
+                    self.is_open(current, direction)
+                        && self.neighbor(current, direction).is_some_and(|next| distances.get(next) == Some(distance - 1))
+                })?;
+                current = self.neighbor(current, direction).unwrap();
+                cells.push(current);
+                directions.push(direction.opposite());
+                distance -= 1;
+            }
+            cells.reverse();
+            directions.reverse();
+            Some(Path { cells, directions })
+        }
+
+        // binary tree: every cell opens towards one of the two directions of `bias`, or the only one of them
+        // still inside the grid along the edges. The corner the bias points at opens nowhere and is the root.
+        pub fn generate_binary_maze<R: Rng + ?Sized>(&mut self, bias: BinaryBias, rng: &mut R) -> Result<(), MazeError> {
+            self.check_unmasked("binary")?;
+            let width = self.width;
+            let height = self.height;
+            self.close_all_walls();
+            let directions = bias.directions();
+            for row in 0..height {
+                for col in 0..width {
+                    self.record(GenerationEvent::Visit((row, col)));
+                    let candidates: Vec<&Direction> = directions.iter()
+                        .filter(|direction| Self::neighbor_coord((row, col), direction, width, height).is_some())
+                        .collect();
+                    if let Some(direction) = candidates.choose(rng) {
+                        self.set_cell_wall((row, col), direction, false);
+                    }
+                }
+            }
+            self.refresh_end_and_cost();
+            Ok(())
+        }
+
+        // the enabled neighbours of `coord` that are not in `acc`, with the direction to reach them.
+        fn get_next(&self, coord: Coord, acc: &HashSet<Coord>) -> Vec<(Coord, Direction)> {
+            [Direction::South, Direction::North, Direction::East, Direction::West].into_iter()
+                .filter_map(|direction| self.neighbor(coord, &direction).map(|next| (next, direction)))
+                .filter(|(next, _)| !acc.contains(next))
+                .collect()
+        }
+
+        pub fn generate_random_walk_maze<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+            let width = self.width;
+            let height = self.height;
+            let mut start = self.random_enabled_cell(rng);
+            let area = self.enabled_count();
+            let mut acc: HashSet<(usize, usize)> = HashSet::with_capacity(area);
+
+            while acc.len() < area {
+                if acc.insert(start) {
+                    self.record(GenerationEvent::Visit(start));
+                }
+                match self.get_next(start, &acc).choose(rng) {
+                    None => {
+                        // restart the walk from the first cell it has not reached yet.
+                        if let Some(coord) = (0..height)
+                            .flat_map(|row| (0..width).map(move |col| (row, col)))
+                            .find(|coord| !acc.contains(coord) && self.is_enabled(*coord))
+                        {
+                            start = coord;
+                        }
+                    }
+                    Some(((x, y), direction)) => {
+                        // if *x == 0 {
+                        // println!("{:?}", ((x, y), direction));
+                        self.set_cell_wall((*x, *y), &direction.opposite(), false);
+                        // }
+                        start = (*x, *y);
+                    }
+                }
+            }
+        }
+
+        pub fn find_farthest_point(&self, start: &(usize, usize), _width: usize, _height: usize) -> (usize, usize) {
+            let mut coord_with_max_distance = (*start, 0);
+            self.breadth_first(*start, |coord, distance| {
+                if coord_with_max_distance.1 < distance {
+                    coord_with_max_distance = (coord, distance);
+                }
+            });
+            coord_with_max_distance.0
+        }
+
+        pub fn shuffle<T: Default, R: Rng + ?Sized>(mut collection: Vec<T>, rng: &mut R) -> Vec<T> {
+            for index in 0..collection.len() {
+                let length = collection.len();
+                let new_range = (index as usize)..length;
+                if !new_range.is_empty() {
+                    collection.swap(index, rng.gen_range(new_range));
+                }
+            }
+            collection
+        }
+
+        // depth-first backtracker: walk to a random unvisited neighbour until stuck, then back up to the last cell
+        // that still has one. Gives long winding corridors. The path is kept on an explicit stack of cell indexes
+        // and visits in a bitmap, so it handles grids with hundreds of millions of cells without recursing.
+        pub fn generate_dfs<R: Rng + ?Sized>(&mut self, start: Coord, rng: &mut R) -> Result<(), MazeError> {
+            self.check_cell(start)?;
+            self.close_all_walls();
+            self.carve_dfs(start, rng);
+            self.refresh_end_and_cost();
+            Ok(())
+        }
+
+        // the carving part of `generate_dfs`, expects every wall closed and leaves `end` and `cost` alone.
+        fn carve_dfs<R: Rng + ?Sized>(&mut self, start: Coord, rng: &mut R) {
+            let width = self.width;
+            let height = self.height;
+            // `blank` keeps the cell count below u32::MAX, so indexes fit in half the space of a usize.
+            let mut visited = Bits::new(width * height, false);
+            let mut stack: Vec<u32> = vec![(start.0 * width + start.1) as u32];
+            visited.set(start.0 * width + start.1, true);
+            self.record(GenerationEvent::Visit(start));
+            while let Some(&index) = stack.last() {
+                let coord = (index as usize / width, index as usize % width);
+                // the unvisited neighbours, kept in a fixed array so the hot loop never allocates.
+                let mut candidates: [(usize, Coord); 4] = [(0, (0, 0)); 4];
+                let mut count = 0;
+                for (position, direction) in DIRECTIONS.iter().enumerate() {
+                    if let Some(next) = self.neighbor(coord, direction) {
+                        if !visited.get(next.0 * width + next.1) {
+                            candidates[count] = (position, next);
+                            count += 1;
+                        }
+                    }
+                }
+                let (position, next) = match count {
+                    0 => {
+                        stack.pop();
+                        self.record(GenerationEvent::Backtrack(coord));
+                        continue;
+                    }
+                    1 => candidates[0],
+                    _ => candidates[rng.gen_range(0..count)],
+                };
+                self.set_cell_wall(coord, &DIRECTIONS[position], false);
+                visited.set(next.0 * width + next.1, true);
+                self.record(GenerationEvent::Visit(next));
+                stack.push((next.0 * width + next.1) as u32);
+            }
+        }
+
+        // Kruskal's algorithm: visit every interior wall in random order and knock it down whenever the
+        // cells on either side are not yet connected. Produces many short dead ends.
+        pub fn generate_kruskal<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+            let width = self.width;
+            let height = self.height;
+            self.close_all_walls();
+            let mut edges: Vec<(Coord, Direction)> = Vec::with_capacity(2 * width * height);
+            for (row, col) in self.cells() {
+                if self.is_enabled((row, col + 1)) {
+                    edges.push(((row, col), Direction::East));
+                }
+                if self.is_enabled((row + 1, col)) {
+                    edges.push(((row, col), Direction::South));
+                }
+            }
+            edges.shuffle(rng);
+            let mut sets = DisjointSet::new(width * height);
+            for ((row, col), direction) in edges {
+                let (next_row, next_col) = match direction {
+                    Direction::South => (row + 1, col),
+                    _ => (row, col + 1),
+                };
+                if sets.union(row * width + col, next_row * width + next_col) {
+                    self.set_cell_wall((row, col), &direction, false);
+                }
+            }
+            self.refresh_end_and_cost();
+        }
+
+        // the coordinate one step away in `direction`, ignoring walls. None if it falls outside the grid.
+        fn neighbor_coord(coord: Coord, direction: &Direction, width: usize, height: usize) -> Option<Coord> {
+            let (row, col) = coord;
+            match direction {
+                Direction::North if row > 0 => Some((row - 1, col)),
+                Direction::South if row + 1 < height => Some((row + 1, col)),
+                Direction::West if col > 0 => Some((row, col - 1)),
+                Direction::East if col + 1 < width => Some((row, col + 1)),
+                _ => None,
+            }
+        }
+
+        // randomized Prim's algorithm: grow the maze from `start` by repeatedly attaching a random cell from the
+        // frontier (cells next to the maze but not in it yet). Gives a radial texture with lots of short spikes.
+        pub fn generate_prim<R: Rng + ?Sized>(&mut self, start: Coord, rng: &mut R) -> Result<(), MazeError> {
+            let width = self.width;
+            let height = self.height;
+            self.check_cell(start)?;
+            self.close_all_walls();
+            let mut acc: HashSet<Coord> = HashSet::with_capacity(width * height);
+            let mut frontier: Vec<Coord> = Vec::new();
+            let mut in_frontier: HashSet<Coord> = HashSet::new();
+            let mut next = Some(start);
+            while let Some(coord) = next {
+                acc.insert(coord);
+                self.record(GenerationEvent::Visit(coord));
+                self.get_next(coord, &acc).into_iter().for_each(|(neighbor, _)| {
+                    if in_frontier.insert(neighbor) {
+                        frontier.push(neighbor);
+                    }
+                });
+                next = if frontier.is_empty() {
+                    None
+                } else {
+                    let cell = frontier.swap_remove(rng.gen_range(0..frontier.len()));
+                    let connections: Vec<&Direction> = DIRECTIONS.iter()
+                        .filter(|direction| {
+                            self.neighbor(cell, direction).is_some_and(|x| acc.contains(&x))
+                        }).collect();
+                    if let Some(direction) = connections.choose(rng) {
+                        self.set_cell_wall(cell, direction, false);
+                    }
+                    Some(cell)
+                };
+            }
+            self.refresh_end_and_cost();
+            Ok(())
+        }
+
+        // Wilson's algorithm: loop-erased random walks from every cell not yet in the maze until they hit it.
+        // Samples uniformly from all perfect mazes of this size.
+        pub fn generate_wilson<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+            let width = self.width;
+            let height = self.height;
+            self.close_all_walls();
+            let mut acc: HashSet<Coord> = HashSet::with_capacity(width * height);
+            let first = self.random_enabled_cell(rng);
+            acc.insert(first);
+            self.record(GenerationEvent::Visit(first));
+            // the last direction taken out of each cell; overwriting it on revisits erases the loops.
+            let mut walk: HashMap<Coord, Direction> = HashMap::new();
+            for row in 0..height {
+                for col in 0..width {
+                    let mut current = (row, col);
+                    while !acc.contains(&current) && self.is_enabled(current) {
+                        let (direction, next) = DIRECTIONS.iter()
+                            .filter_map(|direction| self.neighbor(current, direction).map(|x| (direction, x)))
+                            .collect::<Vec<(&Direction, Coord)>>()
+                            .choose(rng)
+                            .map(|(direction, next)| ((*direction).clone(), *next))
+                            .expect("a connected grid with more than one cell always has a neighbour");
+                        walk.insert(current, direction);
+                        current = next;
+                    }
+                    current = (row, col);
+                    while !acc.contains(&current) && self.is_enabled(current) {
+                        let direction = walk.remove(&current).unwrap();
+                        acc.insert(current);
+                        self.record(GenerationEvent::Visit(current));
+                        self.set_cell_wall(current, &direction, false);
+                        current = Self::neighbor_coord(current, &direction, width, height).unwrap();
+                    }
+                    walk.clear();
+                }
+            }
+            self.refresh_end_and_cost();
+        }
+
+        // Aldous-Broder: a plain random walk that carves into every cell it enters for the first time. Samples
+        // uniformly like Wilson's but can take very long on big grids, so `progress` gets (visited, total) each
+        // time a new cell is reached, and the walk gives up after `max_steps` moves if a budget is given. The walls
+        // carved before giving up are kept, and `end`/`cost` are refreshed either way.
+        pub fn generate_aldous_broder<R: Rng + ?Sized, F: FnMut(usize, usize)>(&mut self, max_steps: Option<usize>, rng: &mut R, mut progress: F) -> Result<(), MazeError> {
+            let total = self.enabled_count();
+            self.close_all_walls();
+            let mut acc: HashSet<Coord> = HashSet::with_capacity(total);
+            let mut current = self.random_enabled_cell(rng);
+            acc.insert(current);
+            self.record(GenerationEvent::Visit(current));
+            progress(acc.len(), total);
+            let mut steps = 0;
+            let mut result = Ok(());
+            while acc.len() < total {
+                if max_steps.is_some_and(|max_steps| steps >= max_steps) {
+                    result = Err(MazeError::StepBudgetExhausted { steps, visited: acc.len(), total });
+                    break;
+                }
+                steps += 1;
+                let (direction, next) = DIRECTIONS.iter()
+                    .filter_map(|direction| self.neighbor(current, direction).map(|x| (direction, x)))
+                    .collect::<Vec<(&Direction, Coord)>>()
+                    .choose(rng)
+                    .map(|(direction, next)| ((*direction).clone(), *next))
+                    .expect("a connected grid with more than one cell always has a neighbour");
+                if acc.insert(next) {
+                    self.set_cell_wall(current, &direction, false);
+                    self.record(GenerationEvent::Visit(next));
+                    progress(acc.len(), total);
+                }
+                current = next;
+            }
+            self.refresh_end_and_cost();
+            result
+        }
+
+        // Eller's algorithm, writing into this maze row by row. See `eller_rows`.
+        pub fn generate_eller<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Result<(), MazeError> {
+            self.check_unmasked("eller")?;
+            let width = self.width;
+            let height = self.height;
+            self.close_all_walls();
+            Self::eller_rows(width, height, rng, |row, side_walls, bottom_walls| {
+                (1..width).filter(|col| !side_walls[*col]).for_each(|col| self.set_cell_wall((row, col), &Direction::West, false));
+                (0..width).filter(|col| !bottom_walls[*col]).for_each(|col| self.set_cell_wall((row, col), &Direction::South, false));
+            });
+            self.refresh_end_and_cost();
+            Ok(())
+        }
+
+        // Eller's algorithm: builds the maze one row at a time, keeping only the set membership of the current
+        // row, so memory is proportional to `width` no matter how tall the maze is. For every finished row `emit`
+        // gets the row index, its `width + 1` side walls (`horizontal_wall(row, col)`) and its `width` bottom walls
+        // (`vertical_wall(row + 1, col)`). The top of row 0 is always walled.
+        pub fn eller_rows<R: Rng + ?Sized, F: FnMut(usize, &[bool], &[bool])>(width: usize, height: usize, rng: &mut R, mut emit: F) {
+            // set id of every cell in the current row; ids are always below `width`.
+            let mut sets: Vec<usize> = (0..width).collect();
+            let mut side_walls = vec![true; width + 1];
+            let mut bottom_walls = vec![true; width];
+            let mut members: Vec<Vec<usize>> = vec![Vec::new(); width];
+            let mut used = vec![false; width];
+            for row in 0..height {
+                let last_row = row + 1 == height;
+                let mut joined = DisjointSet::new(width);
+                side_walls.iter_mut().for_each(|wall| *wall = true);
+                for col in 0..width.saturating_sub(1) {
+                    if joined.find(sets[col]) != joined.find(sets[col + 1]) && (last_row || rng.gen_bool(0.5)) {
+                        joined.union(sets[col], sets[col + 1]);
+                        side_walls[col + 1] = false;
+                    }
+                }
+                sets.iter_mut().for_each(|set| *set = joined.find(*set));
+
+                bottom_walls.iter_mut().for_each(|wall| *wall = true);
+                if !last_row {
+                    members.iter_mut().for_each(|x| x.clear());
+                    sets.iter().enumerate().for_each(|(col, set)| members[*set].push(col));
+                    // every set has to continue downwards at least once or it would be cut off.
+                    for cols in members.iter().filter(|cols| !cols.is_empty()) {
+                        cols.iter().for_each(|col| bottom_walls[*col] = !rng.gen_bool(0.5));
+                        if cols.iter().all(|col| bottom_walls[*col]) {
+                            bottom_walls[*cols.choose(rng).unwrap()] = false;
+                        }
+                    }
+                }
+                emit(row, &side_walls, &bottom_walls);
+
+                // cells below an open bottom wall keep their set, the others start a new one.
+                used.iter_mut().for_each(|x| *x = false);
+                (0..width).filter(|col| !bottom_walls[*col]).for_each(|col| used[sets[col]] = true);
+                let mut free = (0..width).filter(|set| !used[*set]);
+                for col in 0..width {
+                    if bottom_walls[col] {
+                        sets[col] = free.next().unwrap();
+                    }
+                }
+            }
+        }
+
+        // growing tree: keep a list of active cells, carve from one of them chosen by `selection` and drop it
+        // once it has no unvisited neighbours left. Covers everything from DFS-like to Prim-like textures.
+        pub fn generate_growing_tree<R: Rng + ?Sized>(&mut self, start: Coord, selection: &CellSelection, rng: &mut R) -> Result<(), MazeError> {
+            let width = self.width;
+            let height = self.height;
+            self.check_cell(start)?;
+            self.close_all_walls();
+            let mut active: VecDeque<Coord> = VecDeque::with_capacity(width * height);
+            let mut acc: HashSet<Coord> = HashSet::with_capacity(width * height);
+            active.push_back(start);
+            acc.insert(start);
+            self.record(GenerationEvent::Visit(start));
+            while !active.is_empty() {
+                let index = selection.pick(active.len(), rng);
+                let current = active[index];
+                match self.get_next(current, &acc).choose(rng) {
+                    Some((next, direction)) => {
+                        self.set_cell_wall(current, direction, false);
+                        acc.insert(*next);
+                        self.record(GenerationEvent::Visit(*next));
+                        active.push_back(*next);
+                    }
+                    None => {
+                        active.remove(index);
+                        self.record(GenerationEvent::Backtrack(current));
+                    }
+                }
+            }
+            self.refresh_end_and_cost();
+            Ok(())
+        }
+
+        // recursive division: start from an open grid and split every chamber with a wall that has a single gap
+        // in it. Chambers that fit within `room_size` x `room_size` are left open as rooms; with `None` every
+        // chamber is divided down to corridors and the result is a perfect maze.
+        pub fn generate_recursive_division<R: Rng + ?Sized>(&mut self, room_size: Option<usize>, rng: &mut R) -> Result<(), MazeError> {
+            self.check_unmasked("recursive-division")?;
+            let width = self.width;
+            let height = self.height;
+            self.close_all_walls();
+            for row in 0..height {
+                for col in 0..width {
+                    if col + 1 < width {
+                        self.set_cell_wall((row, col), &Direction::East, false);
+                    }
+                    if row + 1 < height {
+                        self.set_cell_wall((row, col), &Direction::South, false);
+                    }
+                }
+            }
+            // chambers still to divide, as (top row, left col, height, width).
+            let mut chambers: Vec<(usize, usize, usize, usize)> = vec![(0, 0, height, width)];
+            while let Some((row, col, chamber_height, chamber_width)) = chambers.pop() {
+                if chamber_height < 2 && chamber_width < 2 {
+                    continue;
+                }
+                if room_size.is_some_and(|size| chamber_height <= size && chamber_width <= size) {
+                    continue;
+                }
+                let split_rows = match chamber_height.cmp(&chamber_width) {
+                    std::cmp::Ordering::Greater => true,
+                    std::cmp::Ordering::Less => false,
+                    std::cmp::Ordering::Equal => rng.gen_bool(0.5),
+                };
+                if split_rows {
+                    // wall along the bottom of `cut`, with a gap below `gap`.
+                    let cut = row + rng.gen_range(0..chamber_height - 1);
+                    let gap = col + rng.gen_range(0..chamber_width);
+                    (col..col + chamber_width).filter(|x| *x != gap).for_each(|x| {
+                        self.set_cell_wall((cut, x), &Direction::South, true);
+                    });
+                    chambers.push((row, col, cut + 1 - row, chamber_width));
+                    chambers.push((cut + 1, col, row + chamber_height - cut - 1, chamber_width));
+                } else {
+                    // wall along the right of `cut`, with a gap next to `gap`.
+                    let cut = col + rng.gen_range(0..chamber_width - 1);
+                    let gap = row + rng.gen_range(0..chamber_height);
+                    (row..row + chamber_height).filter(|x| *x != gap).for_each(|x| {
+                        self.set_cell_wall((x, cut), &Direction::East, true);
+                    });
+                    chambers.push((row, col, chamber_height, cut + 1 - col));
+                    chambers.push((row, cut + 1, chamber_height, col + chamber_width - cut - 1));
+                }
+            }
+            self.refresh_end_and_cost();
+            Ok(())
+        }
+
+        // hunt-and-kill: random walk from `start` until stuck, then hunt row by row for an unvisited cell next to
+        // the carved area, connect it and walk on from there. Long corridors like DFS, but no stack.
+        pub fn generate_hunt_and_kill<R: Rng + ?Sized>(&mut self, start: Coord, rng: &mut R) -> Result<(), MazeError> {
+            let width = self.width;
+            let height = self.height;
+            self.check_cell(start)?;
+            self.close_all_walls();
+            let mut acc: HashSet<Coord> = HashSet::with_capacity(width * height);
+            // rows above `hunt_row` are completely visited and never need to be scanned again.
+            let mut visited_per_row: Vec<usize> = (0..height)
+                .map(|row| (0..width).filter(|col| !self.is_enabled((row, *col))).count())
+                .collect();
+            let mut hunt_row = 0;
+            let mut current = Some(start);
+            while let Some(coord) = current {
+                if acc.insert(coord) {
+                    visited_per_row[coord.0] += 1;
+                    self.record(GenerationEvent::Visit(coord));
+                }
+                let next = match self.get_next(coord, &acc).choose(rng) {
+                    Some((next, direction)) => Some((coord, *next, direction.clone())),
+                    None => {
+                        while hunt_row < height && visited_per_row[hunt_row] == width {
+                            hunt_row += 1;
+                        }
+                        (hunt_row..height)
+                            .flat_map(|row| (0..width).map(move |col| (row, col)))
+                            .filter(|coord| !acc.contains(coord) && self.is_enabled(*coord))
+                            .find_map(|coord| {
+                                let connections: Vec<&Direction> = DIRECTIONS.iter()
+                                    .filter(|direction| self.neighbor(coord, direction).is_some_and(|x| acc.contains(&x)))
+                                    .collect();
+                                connections.choose(rng).map(|direction| (coord, coord, (*direction).clone()))
+                            })
+                    }
+                };
+                // the wall is carved from `from`, which is `coord` when walking on and the hunted cell otherwise.
+                current = next.map(|(from, next, direction)| {
+                    self.set_cell_wall(from, &direction, false);
+                    next
+                });
+            }
+            self.refresh_end_and_cost();
+            Ok(())
+        }
+
+        // sidewinder: the top row is one corridor, every other row is cut into random runs heading east and each
+        // run opens north from one random cell. Only the top row has the long straight bias.
+        pub fn generate_sidewinder<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Result<(), MazeError> {
+            self.check_unmasked("sidewinder")?;
+            let width = self.width;
+            let height = self.height;
+            self.close_all_walls();
+            let mut run: Vec<usize> = Vec::with_capacity(width);
+            for row in 0..height {
+                run.clear();
+                for col in 0..width {
+                    run.push(col);
+                    self.record(GenerationEvent::Visit((row, col)));
+                    let at_east_edge = col + 1 == width;
+                    if row > 0 && (at_east_edge || rng.gen_bool(0.5)) {
+                        let north = *run.choose(rng).unwrap();
+                        self.set_cell_wall((row, north), &Direction::North, false);
+                        run.clear();
+                    } else if !at_east_edge {
+                        self.set_cell_wall((row, col), &Direction::East, false);
+                    }
+                }
+            }
+            self.refresh_end_and_cost();
+            Ok(())
+        }
+
+        // splits the grid into `tile_size` square tiles (smaller along the east and south edges), carves every tile
+        // with its own DFS on the rayon thread pool, then joins them through one random door per edge of a random
+        // spanning tree over the tiles, so the whole maze stays perfect. The tile seeds are drawn from `rng` up
+        // front in tile order, which keeps the result independent of the number of threads.
+        pub fn generate_tiled<R: Rng + ?Sized>(&mut self, tile_size: usize, rng: &mut R) -> Result<(), MazeError> {
+            self.check_unmasked("tiled")?;
+            let width = self.width;
+            let height = self.height;
+            let tile_size = tile_size.max(1);
+            let tiles_across = width.div_ceil(tile_size);
+            let tiles_down = height.div_ceil(tile_size);
+            self.close_all_walls();
+            let origins: Vec<Coord> = (0..tiles_down)
+                .flat_map(|row| (0..tiles_across).map(move |col| (row * tile_size, col * tile_size)))
+                .collect();
+            let seeds: Vec<u64> = origins.iter().map(|_| rng.next_u64()).collect();
+            let tiles: Vec<Maze> = origins.par_iter().zip(seeds.par_iter()).map(|(&(row, col), &seed)| {
+                let mut tile = Maze::blank(tile_size.min(width - col), tile_size.min(height - row), (0, 0)).unwrap();
+                tile.carve_dfs((0, 0), &mut rng_from_seed(seed));
+                tile
+            }).collect();
+            for (&(row, col), tile) in origins.iter().zip(tiles.iter()) {
+                for (a, b) in tile.passages() {
+                    let direction = if a.0 == b.0 { Direction::East } else { Direction::South };
+                    self.set_cell_wall((row + a.0, col + a.1), &direction, false);
+                }
+            }
+
+            let mut doors: Vec<(usize, Direction)> = Vec::with_capacity(2 * origins.len());
+            for tile in 0..origins.len() {
+                if tile % tiles_across + 1 < tiles_across {
+                    doors.push((tile, Direction::East));
+                }
+                if tile / tiles_across + 1 < tiles_down {
+                    doors.push((tile, Direction::South));
+                }
+            }
+            doors.shuffle(rng);
+            let mut sets = DisjointSet::new(origins.len());
+            for (tile, direction) in doors {
+                let next = match direction {
+                    Direction::East => tile + 1,
+                    _ => tile + tiles_across,
+                };
+                if sets.union(tile, next) {
+                    // a tile with a neighbour to the east or south is always full size in that direction.
+                    let (row, col) = origins[tile];
+                    let cell = match direction {
+                        Direction::East => (row + rng.gen_range(0..tile_size.min(height - row)), col + tile_size - 1),
+                        _ => (row + tile_size - 1, col + rng.gen_range(0..tile_size.min(width - col))),
+                    };
+                    self.set_cell_wall(cell, &direction, false);
+                }
+            }
+            self.refresh_end_and_cost();
+            Ok(())
+        }
+    }
+
+    pub trait MazeGenerator: Send + Sync {
+        // the name the generator is registered under, e.g. "kruskal".
+        fn name(&self) -> &str;
+
+        // replaces the walls of `maze` with freshly generated ones, and leaves `end` and `cost` consistent with
+        // them (see `Maze::refresh_end_and_cost`). Only some generators grow the maze from `maze.start`; the
+        // others carve the whole grid at once and `start` only matters for `end` and `cost`.
+        fn generate(&self, maze: &mut Maze, rng: &mut dyn RngCore) -> Result<(), MazeError>;
+
+        // whether `generate` always gives a perfect maze: every cell reachable from `start` and exactly one
+        // path between any two cells.
+        fn is_perfect(&self) -> bool {
+            true
+        }
+    }
+
+    pub struct DfsBacktracker;
+
+    impl MazeGenerator for DfsBacktracker {
+        fn name(&self) -> &str {
+            "dfs"
+        }
+
+        fn generate(&self, maze: &mut Maze, rng: &mut dyn RngCore) -> Result<(), MazeError> {
+            maze.generate_dfs(maze.start, rng)
+        }
+    }
+
+    pub struct RandomWalk;
+
+    impl MazeGenerator for RandomWalk {
+        fn name(&self) -> &str {
+            "random-walk"
+        }
+
+        fn generate(&self, maze: &mut Maze, rng: &mut dyn RngCore) -> Result<(), MazeError> {
+            maze.close_all_walls();
+            maze.generate_random_walk_maze(rng);
+            maze.refresh_end_and_cost();
+            Ok(())
+        }
+
+        // the walk jumps to an unvisited cell when it gets stuck without connecting it, so parts of the maze
+        // can be cut off from `start`.
+        fn is_perfect(&self) -> bool {
+            false
+        }
+    }
+
+    pub struct BinaryTree {
+        pub bias: BinaryBias,
+    }
+
+    impl MazeGenerator for BinaryTree {
+        fn name(&self) -> &str {
+            "binary"
+        }
+
+        fn generate(&self, maze: &mut Maze, rng: &mut dyn RngCore) -> Result<(), MazeError> {
+            maze.generate_binary_maze(self.bias, rng)
+        }
+    }
+
+    pub struct Kruskal;
+
+    impl MazeGenerator for Kruskal {
+        fn name(&self) -> &str {
+            "kruskal"
+        }
+
+        fn generate(&self, maze: &mut Maze, rng: &mut dyn RngCore) -> Result<(), MazeError> {
+            maze.generate_kruskal(rng);
+            Ok(())
+        }
+    }
+
+    pub struct Prim;
+
+    impl MazeGenerator for Prim {
+        fn name(&self) -> &str {
+            "prim"
+        }
+
+        fn generate(&self, maze: &mut Maze, rng: &mut dyn RngCore) -> Result<(), MazeError> {
+            maze.generate_prim(maze.start, rng)
+        }
+    }
+
+    pub struct Wilson;
+
+    impl MazeGenerator for Wilson {
+        fn name(&self) -> &str {
+            "wilson"
+        }
+
+        fn generate(&self, maze: &mut Maze, rng: &mut dyn RngCore) -> Result<(), MazeError> {
+            maze.generate_wilson(rng);
+            Ok(())
+        }
+    }
+
+    pub struct AldousBroder;
+
+    impl MazeGenerator for AldousBroder {
+        fn name(&self) -> &str {
+            "aldous-broder"
+        }
+
+        fn generate(&self, maze: &mut Maze, rng: &mut dyn RngCore) -> Result<(), MazeError> {
+            maze.generate_aldous_broder(None, rng, |_, _| {})
+        }
+    }
+
+    pub struct Eller;
+
+    impl MazeGenerator for Eller {
+        fn name(&self) -> &str {
+            "eller"
+        }
+
+        fn generate(&self, maze: &mut Maze, rng: &mut dyn RngCore) -> Result<(), MazeError> {
+            maze.generate_eller(rng)
+        }
+    }
+
+    pub struct GrowingTree {
+        pub selection: CellSelection,
+    }
+
+    impl MazeGenerator for GrowingTree {
+        fn name(&self) -> &str {
+            "growing-tree"
+        }
+
+        fn generate(&self, maze: &mut Maze, rng: &mut dyn RngCore) -> Result<(), MazeError> {
+            maze.generate_growing_tree(maze.start, &self.selection, rng)
+        }
+    }
+
+    pub struct RecursiveDivision {
+        pub room_size: Option<usize>,
+    }
+
+    impl MazeGenerator for RecursiveDivision {
+        fn name(&self) -> &str {
+            "recursive-division"
+        }
+
+        fn generate(&self, maze: &mut Maze, rng: &mut dyn RngCore) -> Result<(), MazeError> {
+            maze.generate_recursive_division(self.room_size, rng)
+        }
+
+        // rooms are open areas, so there is more than one path through them.
+        fn is_perfect(&self) -> bool {
+            self.room_size.is_none()
+        }
+    }
+
+    pub struct HuntAndKill;
+
+    impl MazeGenerator for HuntAndKill {
+        fn name(&self) -> &str {
+            "hunt-and-kill"
+        }
+
+        fn generate(&self, maze: &mut Maze, rng: &mut dyn RngCore) -> Result<(), MazeError> {
+            maze.generate_hunt_and_kill(maze.start, rng)
+        }
+    }
+
+    pub struct Sidewinder;
+
+    impl MazeGenerator for Sidewinder {
+        fn name(&self) -> &str {
+            "sidewinder"
+        }
+
+        fn generate(&self, maze: &mut Maze, rng: &mut dyn RngCore) -> Result<(), MazeError> {
+            maze.generate_sidewinder(rng)
+        }
+    }
+
+    // DFS tiles generated in parallel and stitched together, for very large mazes. See `Maze::generate_tiled`.
+    pub struct Tiled {
+        pub tile_size: usize,
+    }
+
+    impl MazeGenerator for Tiled {
+        fn name(&self) -> &str {
+            "tiled"
+        }
+
+        fn generate(&self, maze: &mut Maze, rng: &mut dyn RngCore) -> Result<(), MazeError> {
+            maze.generate_tiled(self.tile_size, rng)
+        }
+    }
+
+    // generators looked up by `MazeGenerator::name`, in registration order.
+    pub struct GeneratorRegistry {
+        generators: Vec<Arc<dyn MazeGenerator>>,
+    }
+
+    impl GeneratorRegistry {
+        pub fn empty() -> Self {
+            GeneratorRegistry { generators: Vec::new() }
+        }
+
+        // adds `generator`, replacing any generator already registered under the same name.
+        pub fn register(&mut self, generator: Box<dyn MazeGenerator>) {
+            let generator: Arc<dyn MazeGenerator> = Arc::from(generator);
+            match self.generators.iter().position(|x| x.name() == generator.name()) {
+                Some(index) => self.generators[index] = generator,
+                None => self.generators.push(generator),
+            }
+        }
+
+        pub fn get(&self, name: &str) -> Option<&dyn MazeGenerator> {
+            self.generators.iter().find(|x| x.name() == name).map(|x| x.as_ref())
+        }
+
+        // like `get`, for callers that need to keep the generator, e.g. `Maze::generation_steps`.
+        pub fn get_shared(&self, name: &str) -> Option<Arc<dyn MazeGenerator>> {
+            self.generators.iter().find(|x| x.name() == name).cloned()
+        }
+
+        pub fn names(&self) -> Vec<&str> {
+            self.generators.iter().map(|x| x.name()).collect()
+        }
+    }
+
+    // every built-in generator, with the DFS backtracker `Maze::new` uses first.
+    impl Default for GeneratorRegistry {
+        fn default() -> Self {
+            let mut registry = Self::empty();
+            registry.register(Box::new(DfsBacktracker));
+            registry.register(Box::new(RandomWalk));
+            registry.register(Box::new(BinaryTree { bias: BinaryBias::NorthEast }));
+            registry.register(Box::new(Kruskal));
+            registry.register(Box::new(Prim));
+            registry.register(Box::new(Wilson));
+            registry.register(Box::new(AldousBroder));
+            registry.register(Box::new(Eller));
+            registry.register(Box::new(GrowingTree {
+                selection: CellSelection::Mix(vec![(CellSelection::Newest, 3), (CellSelection::Random, 1)]),
+            }));
+            registry.register(Box::new(RecursiveDivision { room_size: None }));
+            registry.register(Box::new(HuntAndKill));
+            registry.register(Box::new(Sidewinder));
+            registry.register(Box::new(Tiled { tile_size: 64 }));
+            registry
+        }
+    }
+
+    // fixed size bitset packed into u64 words. Bits past `len` in the last word are kept at zero.
+    #[derive(Debug, Clone, PartialEq)]
+    pub(crate) struct Bits {
+        words: Vec<u64>,
+        len: usize,
+    }
+
+    impl Bits {
+        pub(crate) fn new(len: usize, value: bool) -> Self {
+            let mut result = Bits { words: vec![0; len.div_ceil(64)], len };
+            result.fill(value);
+            result
+        }
+
+        pub(crate) fn get(&self, index: usize) -> bool {
+            self.words[index / 64] & (1 << (index % 64)) != 0
+        }
+
+        pub(crate) fn set(&mut self, index: usize, value: bool) {
+            if value {
+                self.words[index / 64] |= 1 << (index % 64);
+            } else {
+                self.words[index / 64] &= !(1 << (index % 64));
+            }
+        }
+
+        pub(crate) fn fill(&mut self, value: bool) {
+            self.words.fill(if value { u64::MAX } else { 0 });
+            if value && !self.len.is_multiple_of(64) {
+                if let Some(last) = self.words.last_mut() {
+                    *last = (1 << (self.len % 64)) - 1;
+                }
+            }
+        }
+    }
+
+    // union-find over cell indexes (`row * width + col`), used to tell whether two cells are already connected.
+    struct DisjointSet {
+        parent: Vec<usize>,
+        rank: Vec<u8>,
+    }
+
+    impl DisjointSet {
+        fn new(size: usize) -> Self {
+            DisjointSet {
+                parent: (0..size).collect(),
+                rank: vec![0; size],
+            }
+        }
+
+        fn find(&mut self, mut index: usize) -> usize {
+            let mut root = index;
+            while self.parent[root] != root {
+                root = self.parent[root];
+            }
+            while self.parent[index] != root {
+                let next = self.parent[index];
+                self.parent[index] = root;
+                index = next;
+            }
+            root
+        }
+
+        // returns false if both indexes were already in the same set.
+        fn union(&mut self, a: usize, b: usize) -> bool {
+            let (a, b) = (self.find(a), self.find(b));
+            if a == b {
+                return false;
+            }
+            match self.rank[a].cmp(&self.rank[b]) {
+                std::cmp::Ordering::Less => self.parent[a] = b,
+                std::cmp::Ordering::Greater => self.parent[b] = a,
+                std::cmp::Ordering::Equal => {
+                    self.parent[b] = a;
+                    self.rank[a] += 1;
+                }
+            }
+            true
+        }
+    }
+}
+
+// an endless maze split into square chunks. Every chunk is an ordinary `Maze` generated on demand from the world
+// seed and the chunk coordinates, so unloading a chunk and loading it again gives back the same walls. Each chunk
+// opens one door towards its east and one towards its south neighbour, which keeps the whole world connected.
+pub mod world {
+    use std::collections::HashMap;
+    use crate::maze::{DfsBacktracker, Direction, Maze, MazeError, MazeGenerator};
+
+    // global cell coordinates (row, col), unbounded in every direction.
+    pub type WorldCoord = (i64, i64);
+    // chunk coordinates (row, col); chunk (0, 0) holds the cells from (0, 0) to (chunk_size - 1, chunk_size - 1).
+    pub type ChunkCoord = (i64, i64);
+
+    pub struct World {
+        seed: u64,
+        chunk_size: usize,
+        generator: Box<dyn MazeGenerator>,
+        chunks: HashMap<ChunkCoord, Maze>,
+    }
+
+    impl World {
+        // a world with DFS chunks, see `with_generator`.
+        pub fn new(seed: u64, chunk_size: usize) -> Result<Self, MazeError> {
+            Self::with_generator(seed, chunk_size, Box::new(DfsBacktracker))
+        }
+
+        // fails for a zero `chunk_size`. `generator` must connect every cell of a chunk, or parts of the world
+        // become unreachable.
+        pub fn with_generator(seed: u64, chunk_size: usize, generator: Box<dyn MazeGenerator>) -> Result<Self, MazeError> {
+            Maze::blank(chunk_size, chunk_size, (0, 0))?;
+            Ok(World { seed, chunk_size, generator, chunks: HashMap::new() })
+        }
+
+        pub fn seed(&self) -> u64 {
+            self.seed
+        }
+
+        pub fn chunk_size(&self) -> usize {
+            self.chunk_size
+        }
+
+        // the chunk holding `coord`, and the position of `coord` inside it.
+        pub fn locate(&self, (row, col): WorldCoord) -> (ChunkCoord, (usize, usize)) {
+            let size = self.chunk_size as i64;
+            ((row.div_euclid(size), col.div_euclid(size)), (row.rem_euclid(size) as usize, col.rem_euclid(size) as usize))
+        }
+
+        // generates `chunk` unless it is already loaded.
+        pub fn load(&mut self, chunk: ChunkCoord) -> &Maze {
+            let seed = mix(self.seed, &[chunk.0, chunk.1, 0]);
+            let (size, generator) = (self.chunk_size, &self.generator);
+            self.chunks.entry(chunk).or_insert_with(|| {
+                // the size was checked in `with_generator` and (0, 0) is always inside the chunk.
+                Maze::try_generate_with_seed(size, size, (0, 0), generator.as_ref(), seed).unwrap()
+            })
+        }
+
+        // drops `chunk` from memory, returning false if it was not loaded.
+        pub fn unload(&mut self, chunk: ChunkCoord) -> bool {
+            self.chunks.remove(&chunk).is_some()
+        }
+
+        // drops every chunk further than `radius` chunks from `center` along either axis.
+        pub fn unload_outside(&mut self, center: ChunkCoord, radius: i64) {
+            let near = |a: i64, b: i64| u64::try_from(radius).is_ok_and(|radius| a.abs_diff(b) <= radius);
+            self.chunks.retain(|chunk, _| near(chunk.0, center.0) && near(chunk.1, center.1));
+        }
+
+        pub fn is_loaded(&self, chunk: ChunkCoord) -> bool {
+            self.chunks.contains_key(&chunk)
+        }
+
+        // the loaded chunks, sorted by row then column.
+        pub fn loaded_chunks(&self) -> Vec<ChunkCoord> {
+            let mut result: Vec<ChunkCoord> = self.chunks.keys().cloned().collect();
+            result.sort();
+            result
+        }
+
+        // true if `a` and `b` are adjacent cells with no wall between them. Loads their chunk when both are in the
+        // same one; the doors between chunks are known without generating either side. Cells on opposite ends of
+        // the i64 range are never adjacent.
+        pub fn has_passage(&mut self, a: WorldCoord, b: WorldCoord) -> bool {
+            let (chunk_a, local_a) = self.locate(a);
+            let (chunk_b, local_b) = self.locate(b);
+            if chunk_a == chunk_b {
+                return self.load(chunk_a).has_passage(local_a, local_b);
+            }
+            // crossing a border: only the door of the chunk to the north or west of the border is open.
+            let last = self.chunk_size - 1;
+            match (b.0.checked_sub(a.0), b.1.checked_sub(a.1)) {
+                (Some(0), Some(1)) => local_a.1 == last && self.door(chunk_a, &Direction::East) == local_a.0,
+                (Some(0), Some(-1)) => local_b.1 == last && self.door(chunk_b, &Direction::East) == local_b.0,
+                (Some(1), Some(0)) => local_a.0 == last && self.door(chunk_a, &Direction::South) == local_a.1,
+                (Some(-1), Some(0)) => local_b.0 == last && self.door(chunk_b, &Direction::South) == local_b.1,
+                _ => false,
+            }
+        }
+
+        // the directions you can move in from `coord`, in north, east, south, west order.
+        pub fn open_directions(&mut self, coord: WorldCoord) -> Vec<Direction> {
+            [Direction::North, Direction::East, Direction::South, Direction::West]
+                .into_iter()
+                .filter(|direction| step(coord, direction).is_some_and(|next| self.has_passage(coord, next)))
+                .collect()
+        }
+
+        // the row (for `East`) or column (for `South`) of the door `chunk` opens into its neighbour.
+        fn door(&self, chunk: ChunkCoord, direction: &Direction) -> usize {
+            let side = if *direction == Direction::East { 1 } else { 2 };
+            (mix(self.seed, &[chunk.0, chunk.1, side]) % self.chunk_size as u64) as usize
+        }
+    }
+
+    // the cell next to `coord` in `direction`, None past the edge of the i64 range.
+    pub fn step((row, col): WorldCoord, direction: &Direction) -> Option<WorldCoord> {
+        match direction {
+            Direction::North => Some((row.checked_sub(1)?, col)),
+            Direction::South => Some((row.checked_add(1)?, col)),
+            Direction::West => Some((row, col.checked_sub(1)?)),
+            Direction::East => Some((row, col.checked_add(1)?)),
+        }
+    }
+
+    // splitmix64 over `seed` and `values`. Written out here rather than using `std::hash` so the chunks of a seed
+    // stay the same across Rust versions.
+    fn mix(seed: u64, values: &[i64]) -> u64 {
+        values.iter().fold(seed, |state, value| {
+            let mut z = (state ^ *value as u64).wrapping_add(0x9e3779b97f4a7c15);
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+            z ^ (z >> 31)
+        })
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use crate::maze;
+    use std::sync::Arc;
+
+    // a perfect maze has exactly one path between any two cells: every cell is reachable from `start`
+    // and there are exactly `cells - 1` passages.
+    fn assert_perfect(maze: &maze::Maze) {
+        assert_eq!(maze.passages().count(), maze.cells().count() - 1);
+        for coord in maze.cells() {
+            assert!(maze.cost(coord).is_some(), "{:?} unreachable", coord);
+        }
+    }
+
+    // a grid with only the outer border.
+    fn open_grid(width: usize, height: usize) -> maze::Maze {
+        let mut maze = maze::Maze::blank(width, height, (0, 0)).unwrap();
+        for (row, col) in maze.cells().collect::<Vec<_>>() {
+            if col + 1 < width {
+                maze.set_wall_by_cell(row, col, Some(maze::Direction::East), false).unwrap();
+            }
+            if row + 1 < height {
+                maze.set_wall_by_cell(row, col, Some(maze::Direction::South), false).unwrap();
+            }
+        }
+        maze
+    }
+
+    #[test]
+    fn test_kruskal_is_perfect() {
+        let mut rng = maze::rng_from_seed(1);
+        let mut maze = maze::Maze::new(7, 5, (0, 0));
+        maze.generate_kruskal(&mut rng);
+        assert_perfect(&maze);
+        let mut maze = maze::Maze::new(1, 1, (0, 0));
+        maze.generate_kruskal(&mut rng);
+        assert_perfect(&maze);
+    }
+
+    #[test]
+    fn test_prim_is_perfect() {
+        let mut rng = maze::rng_from_seed(2);
+        let mut maze = maze::Maze::new(6, 9, (0, 0));
+        maze.generate_prim((4, 2), &mut rng).unwrap();
+        assert_perfect(&maze);
+    }
+
+    #[test]
+    fn test_wilson_is_perfect() {
+        let mut rng = maze::rng_from_seed(3);
+        for (width, height) in [(8, 8), (1, 12), (12, 1), (1, 1)] {
+            let mut maze = maze::Maze::new(width, height, (0, 0));
+            maze.generate_wilson(&mut rng);
+            assert_perfect(&maze);
+        }
+    }
+
+    #[test]
+    fn test_aldous_broder_is_perfect() {
+        let mut rng = maze::rng_from_seed(4);
+        let mut maze = maze::Maze::new(9, 4, (0, 0));
+        let mut reports = Vec::new();
+        let result = maze.generate_aldous_broder(None, &mut rng, |visited, total| reports.push((visited, total)));
+        assert_eq!(result, Ok(()));
+        assert_perfect(&maze);
+        assert_eq!(reports.first(), Some(&(1, 36)));
+        assert_eq!(reports.last(), Some(&(36, 36)));
+    }
+
+    #[test]
+    fn test_aldous_broder_step_budget() {
+        let mut rng = maze::rng_from_seed(5);
+        let mut maze = maze::Maze::new(30, 30, (0, 0));
+        match maze.generate_aldous_broder(Some(10), &mut rng, |_, _| {}) {
+            Err(maze::MazeError::StepBudgetExhausted { steps, visited, total }) => {
+                assert_eq!(steps, 10);
+                assert!(visited <= 11);
+                assert_eq!(total, 900);
+            }
+            result => panic!("expected the budget to run out, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_eller_is_perfect() {
+        let mut rng = maze::rng_from_seed(6);
+        for (width, height) in [(10, 30), (1, 5), (5, 1)] {
+            let mut maze = maze::Maze::new(width, height, (0, 0));
+            maze.generate_eller(&mut rng).unwrap();
+            assert_perfect(&maze);
+        }
+    }
+
+    #[test]
+    fn test_eller_rows_streams_every_row() {
+        let mut rng = maze::rng_from_seed(7);
+        let mut rows = Vec::new();
+        maze::Maze::eller_rows(4, 1000, &mut rng, |row, side_walls, bottom_walls| {
+            assert_eq!(side_walls.len(), 5);
+            assert_eq!(bottom_walls.len(), 4);
+            assert!(side_walls[0] && side_walls[4]);
+            rows.push(row);
+        });
+        assert_eq!(rows, (0..1000).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn test_growing_tree_is_perfect() {
+        let mut rng = maze::rng_from_seed(8);
+        let selections = [
+            maze::CellSelection::Newest,
+            maze::CellSelection::Oldest,
+            maze::CellSelection::Random,
+            maze::CellSelection::Middle,
+            maze::CellSelection::Mix(vec![(maze::CellSelection::Newest, 3), (maze::CellSelection::Random, 1)]),
+            maze::CellSelection::Mix(vec![]),
+        ];
+        for selection in selections.iter() {
+            let mut maze = maze::Maze::new(11, 7, (0, 0));
+            maze.generate_growing_tree((3, 5), selection, &mut rng).unwrap();
+            assert_perfect(&maze);
+        }
+    }
+
+    #[test]
+    fn test_recursive_division() {
+        let mut rng = maze::rng_from_seed(9);
+        for (width, height) in [(13, 6), (1, 9), (9, 1)] {
+            let mut maze = maze::Maze::new(width, height, (0, 0));
+            maze.generate_recursive_division(None, &mut rng).unwrap();
+            assert_perfect(&maze);
+        }
+
+        // rooms leave extra passages, but every cell must still be reachable.
+        let mut maze = maze::Maze::new(20, 20, (0, 0));
+        maze.generate_recursive_division(Some(3), &mut rng).unwrap();
+        assert!(maze.cells().all(|x| maze.cost(x).is_some()));
+        assert!(maze.passages().count() > 20 * 20 - 1);
+        assert!(!maze::MazeGenerator::is_perfect(&maze::RecursiveDivision { room_size: Some(3) }));
+        assert!(maze::MazeGenerator::is_perfect(&maze::RecursiveDivision { room_size: None }));
+        // at least one chamber was left as an open room of 2x2 cells or more.
+        let open_square = |(row, col): maze::Coord| {
+            !maze.horizontal_wall(row, col + 1)
+                && !maze.horizontal_wall(row + 1, col + 1)
+                && !maze.vertical_wall(row + 1, col)
+                && !maze.vertical_wall(row + 1, col + 1)
+        };
+        assert!((0..19).flat_map(|row| (0..19).map(move |col| (row, col))).any(open_square));
+        let mut open = maze::Maze::new(3, 3, (0, 0));
+        open.generate_recursive_division(Some(3), &mut rng).unwrap();
+        assert!((0..3).all(|row| !open.horizontal_wall(row, 1) && !open.horizontal_wall(row, 2)));
+    }
+
+    #[test]
+    fn test_hunt_and_kill_is_perfect() {
+        let mut rng = maze::rng_from_seed(10);
+        for (width, height) in [(15, 10), (1, 6), (6, 1)] {
+            let mut maze = maze::Maze::new(width, height, (0, 0));
+            maze.generate_hunt_and_kill((height / 2, width / 2), &mut rng).unwrap();
+            assert_perfect(&maze);
+        }
+    }
+
+    #[test]
+    fn test_sidewinder_is_perfect() {
+        let mut rng = maze::rng_from_seed(11);
+        for (width, height) in [(12, 9), (1, 6), (6, 1)] {
+            let mut maze = maze::Maze::new(width, height, (0, 0));
+            maze.generate_sidewinder(&mut rng).unwrap();
+            assert_perfect(&maze);
+            assert!((1..width).all(|col| !maze.horizontal_wall(0, col)));
+        }
+    }
+
+    #[test]
+    fn test_binary_is_perfect() {
+        let biases = [maze::BinaryBias::NorthEast, maze::BinaryBias::NorthWest, maze::BinaryBias::SouthEast, maze::BinaryBias::SouthWest];
+        for bias in biases {
+            for (width, height) in [(10, 4), (4, 10), (1, 1)] {
+                let maze = maze::Maze::new_binary(width, height, (0, 0), bias);
+                assert_perfect(&maze);
+            }
+        }
+        // with a north-east bias the whole top row is a single corridor.
+        let maze = maze::Maze::new_binary(8, 8, (0, 0), maze::BinaryBias::NorthEast);
+        assert!((1..8).all(|col| !maze.horizontal_wall(0, col)));
+    }
+
+    #[test]
+    fn test_registry_generators_are_perfect() {
+        let registry = maze::GeneratorRegistry::default();
+        assert_eq!(registry.names().first(), Some(&"dfs"));
+        for name in registry.names() {
+            let maze = maze::Maze::generate_with(9, 6, (2, 3), registry.get(name).unwrap());
+            assert_eq!(maze.start(), (2, 3));
+            if registry.get(name).unwrap().is_perfect() {
+                assert_perfect(&maze);
+            }
+        }
+        assert!(registry.get("no-such-generator").is_none());
+    }
+
+    // FNV-1a over the passage coordinates. Written out rather than using `std::hash`, which may change between
+    // Rust versions.
+    fn passages_hash(maze: &maze::Maze) -> u64 {
+        maze.passages()
+            .flat_map(|(a, b)| [a.0, a.1, b.0, b.1])
+            .fold(0xcbf29ce484222325, |hash, value| (hash ^ value as u64).wrapping_mul(0x100000001b3))
+    }
+
+    #[test]
+    fn test_registry_generators_are_pinned_to_their_seed() {
+        // a seed has to keep giving the same maze, so these only change when a generator is changed on purpose.
+        let expected: [(&str, u64); 13] = [
+            ("dfs", 0x26a2fd2560a3a002),
+            ("random-walk", 0xe9155a06a875add4),
+            ("binary", 0xdcbbd4faeba58420),
+            ("kruskal", 0x897a3a5316eabc2a),
+            ("prim", 0x8d217efd58aa62e4),
+            ("wilson", 0xbdc6424cfb228ec8),
+            ("aldous-broder", 0xdc2647bb57e6f65c),
+            ("eller", 0xaac10768257157b0),
+            ("growing-tree", 0xaea3e4df7c1c2ed4),
+            ("recursive-division", 0x68a22623f4b36582),
+            ("hunt-and-kill", 0xb51b2454efddd8b8),
+            ("sidewinder", 0xb64980cee404d912),
+            ("tiled", 0x1a73358a26cdfe3c),
+        ];
+        let registry = maze::GeneratorRegistry::default();
+        assert_eq!(registry.names(), expected.iter().map(|(name, _)| *name).collect::<Vec<&str>>());
+        for (name, hash) in expected {
+            let maze = maze::Maze::generate_with_seed(12, 9, (4, 5), registry.get(name).unwrap(), 2024);
+            assert_eq!(passages_hash(&maze), hash, "{}", name);
+        }
+        // the registered tile size covers this grid with one tile, so pin several tiles and their doors as well.
+        let tiled = maze::Maze::generate_with_seed(12, 9, (4, 5), &maze::Tiled { tile_size: 4 }, 2024);
+        assert_eq!(passages_hash(&tiled), 0xad460376da2ff284);
+    }
+
+    #[test]
+    fn test_registry_replaces_by_name() {
+        let mut registry = maze::GeneratorRegistry::empty();
+        registry.register(Box::new(maze::BinaryTree { bias: maze::BinaryBias::NorthEast }));
+        registry.register(Box::new(maze::BinaryTree { bias: maze::BinaryBias::SouthWest }));
+        assert_eq!(registry.names(), vec!["binary"]);
+        // a south-west bias opens the whole bottom row into one corridor.
+        let maze = maze::Maze::generate_with(5, 5, (0, 0), registry.get("binary").unwrap());
+        assert!((1..5).all(|col| !maze.horizontal_wall(4, col)));
+    }
+
+    #[test]
+    fn test_seeded_generation_is_reproducible() {
+        let registry = maze::GeneratorRegistry::default();
+        for name in registry.names() {
+            let generator = registry.get(name).unwrap();
+            let a = maze::Maze::generate_with_seed(12, 9, (0, 0), generator, 1234);
+            let b = maze::Maze::generate_with_seed(12, 9, (0, 0), generator, 1234);
+            assert_eq!(a.seed(), Some(1234));
+            assert!(a == b, "{}", name);
+        }
+        let dfs = registry.get("dfs").unwrap();
+        let a = maze::Maze::generate_with_seed(12, 9, (0, 0), dfs, 1);
+        let b = maze::Maze::generate_with_seed(12, 9, (0, 0), dfs, 2);
+        assert!(a != b);
+    }
+
+    #[test]
+    fn test_try_new_rejects_bad_input() {
+        assert_eq!(maze::Maze::try_new(0, 5, (0, 0)).err(), Some(maze::MazeError::InvalidDimensions { width: 0, height: 5 }));
+        assert_eq!(maze::Maze::try_new(5, 0, (0, 0)).err(), Some(maze::MazeError::InvalidDimensions { width: 5, height: 0 }));
+        assert!(matches!(maze::Maze::try_new(usize::MAX, 2, (0, 0)), Err(maze::MazeError::InvalidDimensions { .. })));
+        assert_eq!(
+            maze::Maze::try_new(5, 3, (3, 0)).err(),
+            Some(maze::MazeError::StartOutOfBounds { start: (3, 0), width: 5, height: 3 })
+        );
+        assert!(maze::Maze::try_new(5, 3, (2, 4)).is_ok());
+    }
+
+    #[test]
+    fn test_wall_setters_reject_out_of_range() {
+        let mut rng = maze::rng_from_seed(15);
+        let mut maze = maze::Maze::new(4, 3, (0, 0));
+        assert_eq!(
+            maze.set_wall_by_cell(3, 0, Some(maze::Direction::North), false),
+            Err(maze::MazeError::CellOutOfBounds { coord: (3, 0), width: 4, height: 3 })
+        );
+        assert_eq!(maze.set_wall_by_cell(2, 3, Some(maze::Direction::East), false), Ok(()));
+        assert!(!maze.horizontal_wall(2, 4));
+        assert!(maze.generate_prim((0, 4), &mut rng).is_err());
+        assert!(maze.generate_growing_tree((9, 9), &maze::CellSelection::Newest, &mut rng).is_err());
+        assert!(maze.generate_hunt_and_kill((3, 3), &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_set_start_refreshes_cost() {
+        let mut maze = maze::Maze::new(6, 6, (0, 0));
+        assert_eq!(maze.set_start((9, 0)), Err(maze::MazeError::StartOutOfBounds { start: (9, 0), width: 6, height: 6 }));
+        maze.set_start((5, 5)).unwrap();
+        assert_eq!(maze.start(), (5, 5));
+        assert_perfect(&maze);
+        assert_eq!(maze.cost((6, 0)), None);
+        assert!(maze.max_cost().is_some());
+    }
+
+    #[test]
+    fn test_fill_cost() {
+        // a corridor of three cells carved by hand: no costs until `fill_cost`.
+        let mut maze = maze::Maze::blank(3, 1, (0, 0)).unwrap();
+        maze.set_wall_by_cell(0, 0, Some(maze::Direction::East), false).unwrap();
+        maze.set_wall_by_cell(0, 1, Some(maze::Direction::East), false).unwrap();
+        assert_eq!((maze.cost((0, 2)), maze.max_cost()), (None, None));
+        assert_eq!(maze.get_valid_adjascent_cells(0, 1).len(), 2);
+        maze.fill_cost();
+        assert_eq!((maze.cost((0, 0)), maze.cost((0, 2)), maze.max_cost()), (Some(0), Some(2), Some(2)));
+        assert!(maze.get_valid_adjascent_cells(0, 1).is_empty());
+    }
+
+    #[test]
+    fn test_passage_queries() {
+        let mut maze = maze::Maze::new(5, 4, (0, 0));
+        assert_eq!(maze.cells().count(), 20);
+        assert_eq!(maze.cells().last(), Some((3, 4)));
+        for (a, b) in maze.passages() {
+            assert!(maze.has_passage(a, b) && maze.has_passage(b, a));
+            assert!(maze.neighbors(a).contains(&b) && maze.neighbors(b).contains(&a));
+        }
+        for coord in maze.cells() {
+            assert_eq!(maze.open_directions(coord).len(), maze.neighbors(coord).len());
+        }
+
+        // borders never count as passages, even when opened.
+        maze.set_wall_by_cell(0, 0, Some(maze::Direction::North), false).unwrap();
+        assert!(!maze.open_directions((0, 0)).contains(&maze::Direction::North));
+        assert!(!maze.has_passage((0, 0), (0, 2)));
+        assert!(maze.neighbors((7, 7)).is_empty());
+        assert!(maze.open_directions((9, 0)).is_empty());
+    }
+
+    #[test]
+    fn test_generation_steps_replay_to_the_same_maze() {
+        let registry = maze::GeneratorRegistry::default();
+        for name in registry.names() {
+            let generator = registry.get_shared(name).unwrap();
+            let expected = maze::Maze::generate_with_seed(10, 7, (1, 1), generator.as_ref(), 99);
+            let mut replayed = maze::Maze::blank(10, 7, (1, 1)).unwrap();
+            let events: Vec<maze::GenerationEvent> = maze::Maze::generation_steps(10, 7, (1, 1), generator, 99).unwrap().collect();
+            assert_eq!(events.last(), Some(&maze::GenerationEvent::Done), "{}", name);
+            for event in events.iter() {
+                replayed.apply(event).unwrap();
+            }
+            assert!(replayed.passages().eq(expected.passages()), "{}", name);
+            assert_eq!(replayed.end(), expected.end(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_dfs_steps_visit_every_cell_and_backtrack() {
+        let dfs: Arc<dyn maze::MazeGenerator> = Arc::new(maze::DfsBacktracker);
+        let events: Vec<maze::GenerationEvent> = maze::Maze::generation_steps(6, 6, (0, 0), dfs.clone(), 3).unwrap().collect();
+        assert_eq!(events.first(), Some(&maze::GenerationEvent::Visit((0, 0))));
+        assert_eq!(events.iter().filter(|x| matches!(x, maze::GenerationEvent::Visit(_))).count(), 36);
+        assert_eq!(events.iter().filter(|x| matches!(x, maze::GenerationEvent::Carve(_, _))).count(), 35);
+        assert!(events.iter().any(|x| matches!(x, maze::GenerationEvent::Backtrack(_))));
+        assert!(maze::Maze::generation_steps(6, 6, (6, 0), dfs, 3).is_err());
+    }
+
+    #[test]
+    fn test_generation_steps_stop_once_dropped() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        // the dfs backtracker, noting whether it ran to the end.
+        struct Watched(Arc<AtomicBool>);
+        impl maze::MazeGenerator for Watched {
+            fn name(&self) -> &str {
+                "watched"
+            }
+
+            fn generate(&self, maze: &mut maze::Maze, rng: &mut dyn rand::RngCore) -> Result<(), maze::MazeError> {
+                maze::DfsBacktracker.generate(maze, rng)?;
+                self.0.store(true, Ordering::SeqCst);
+                Ok(())
+            }
+        }
+
+        let finished = Arc::new(AtomicBool::new(false));
+        let steps = maze::Maze::generation_steps(4, 4, (0, 0), Arc::new(Watched(finished.clone())), 5).unwrap();
+        assert_eq!(steps.last(), Some(maze::GenerationEvent::Done));
+        assert!(finished.load(Ordering::SeqCst));
+
+        let finished = Arc::new(AtomicBool::new(false));
+        let steps = maze::Maze::generation_steps(300, 300, (0, 0), Arc::new(Watched(finished.clone())), 5).unwrap();
+        assert_eq!(steps.take(10).count(), 10);
+        std::thread::sleep(std::time::Duration::from_millis(500));
+        assert!(!finished.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_dfs_large_and_deep() {
+        let mut rng = maze::rng_from_seed(17);
+        // a single corridor is the deepest possible walk; the recursive version overflowed the stack here.
+        let mut corridor = maze::Maze::new(200_000, 1, (0, 0));
+        corridor.generate_dfs((0, 0), &mut rng).unwrap();
+        assert_eq!(corridor.end(), (0, 199_999));
+        assert_eq!(corridor.cost((0, 199_999)), Some(199_999));
+
+        let mut maze = maze::Maze::new(300, 200, (100, 150));
+        maze.generate_dfs((100, 150), &mut rng).unwrap();
+        assert_perfect(&maze);
+        assert_eq!(maze.cost((100, 150)), Some(0));
+        assert!(maze.generate_dfs((200, 0), &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_tiled_is_perfect_and_independent_of_threads() {
+        let generate = |threads: usize| {
+            let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+            pool.install(|| maze::Maze::generate_with_seed(50, 37, (0, 0), &maze::Tiled { tile_size: 8 }, 18))
+        };
+        let single = generate(1);
+        assert_perfect(&single);
+        assert!(single == generate(4));
+        for tile_size in [0, 1, 7, 100] {
+            let maze = maze::Maze::generate_with_seed(23, 11, (3, 3), &maze::Tiled { tile_size }, 5);
+            assert_perfect(&maze);
+        }
+    }
+
+    #[test]
+    fn test_world_chunks_are_connected_and_reproducible() {
+        use crate::world::World;
+        assert!(World::new(1, 0).is_err());
+        let mut world = World::new(19, 5).unwrap();
+        assert_eq!(world.locate((-1, 7)), ((-1, 1), (4, 2)));
+
+        // every cell of the 3x3 chunks around the origin is reachable from (0, 0) without leaving them.
+        let inside = |(row, col): (i64, i64)| (-5..10).contains(&row) && (-5..10).contains(&col);
+        let mut seen = std::collections::HashSet::from([(0, 0)]);
+        let mut queue = vec![(0, 0)];
+        while let Some(coord) = queue.pop() {
+            for direction in world.open_directions(coord) {
+                let next = crate::world::step(coord, &direction).unwrap();
+                assert!(world.has_passage(next, coord));
+                if inside(next) && seen.insert(next) {
+                    queue.push(next);
+                }
+            }
+        }
+        assert_eq!(seen.len(), 15 * 15);
+        assert_eq!(world.loaded_chunks().len(), 9);
+
+        let before: Vec<Vec<maze::Direction>> = (-5..10).map(|row| world.open_directions((row, row))).collect();
+        world.unload_outside((0, 0), 0);
+        assert_eq!(world.loaded_chunks(), vec![(0, 0)]);
+        assert!(world.unload((0, 0)) && !world.is_loaded((0, 0)));
+        let mut other = World::new(19, 5).unwrap();
+        let after: Vec<Vec<maze::Direction>> = (-5..10).map(|row| other.open_directions((row, row))).collect();
+        assert_eq!(before, after);
+        assert!(!world.has_passage((0, 0), (2, 0)));
+
+        // the far ends of the i64 range are not next to each other.
+        assert_eq!(crate::world::step((0, i64::MAX), &maze::Direction::East), None);
+        assert!(!world.has_passage((0, i64::MAX), (0, i64::MIN)) && !world.has_passage((i64::MIN, 0), (i64::MAX, 0)));
+        assert!(!world.open_directions((i64::MIN, i64::MAX)).iter().any(|x| *x == maze::Direction::North || *x == maze::Direction::East));
+        world.unload_outside((i64::MIN, i64::MIN), 1);
+        assert!(world.loaded_chunks().is_empty());
+    }
+
+    #[test]
+    fn test_masked_mazes() {
+        // a ring: the middle is masked out, so the generators have to go around it.
+        let ring = maze::Mask::from_text("
+            ........
+            ........
+            ..####..
+            ..####..
+            ........
+        ").unwrap();
+        assert_eq!((ring.width(), ring.height(), ring.enabled_count()), (8, 5, 32));
+        assert!(!ring.is_enabled((2, 2)) && ring.is_enabled((4, 7)));
+        assert_eq!(maze::Mask::from_text("..\n.x").err(), Some(maze::MazeError::InvalidMask { row: 1, col: 1 }));
+        assert_eq!(maze::Mask::from_text("..\n.").err(), Some(maze::MazeError::InvalidMask { row: 1, col: 1 }));
+
+        let registry = maze::GeneratorRegistry::default();
+        for name in registry.names() {
+            let result = maze::Maze::try_generate_masked(ring.clone(), (0, 0), registry.get(name).unwrap(), 20);
+            match name {
+                "binary" | "eller" | "recursive-division" | "sidewinder" | "tiled" => {
+                    assert_eq!(result.err(), Some(maze::MazeError::MaskNotSupported(name.to_string())));
+                }
+                _ => {
+                    let maze = result.unwrap();
+                    assert_eq!(maze.cells().count(), 32, "{}", name);
+                    assert!(maze.open_directions((2, 2)).is_empty() && maze.cost((3, 4)).is_none(), "{}", name);
+                    if registry.get(name).unwrap().is_perfect() {
+                        assert_perfect(&maze);
+                    }
+                }
+            }
+        }
+
+        let dfs = maze::DfsBacktracker;
+        let split = maze::Mask::from_text("..#..\n..#..").unwrap();
+        assert_eq!(maze::Maze::try_generate_masked(split, (0, 0), &dfs, 1).err(), Some(maze::MazeError::DisconnectedMask));
+        assert_eq!(maze::Maze::try_generate_masked(ring.clone(), (2, 3), &dfs, 1).err(), Some(maze::MazeError::MaskedCell((2, 3))));
+        let mut maze = maze::Maze::new(4, 4, (0, 0));
+        assert!(maze.set_mask(ring.clone()).is_err());
+
+        // masking a generated maze: the cell-based API stays out of the masked cells as well.
+        let mut maze = maze::Maze::generate_with_seed(8, 5, (0, 0), &maze::Kruskal, 20);
+        maze.set_mask(ring.clone()).unwrap();
+        assert!(maze.get_maze_cell(2, 2).is_none() && maze.get_maze_cell(1, 2).is_some());
+        for coord in maze.cells() {
+            let cell = maze.get_maze_cell(coord.0 as i32, coord.1 as i32).unwrap();
+            for direction in [maze::Direction::North, maze::Direction::East, maze::Direction::South, maze::Direction::West] {
+                assert_eq!(maze.get_cell_by_direction(&cell, &direction).is_some(), maze.open_directions(coord).contains(&direction), "{:?}", coord);
+            }
+        }
+        assert_eq!(maze.set_wall_by_cell(1, 2, Some(maze::Direction::South), false), Err(maze::MazeError::MaskedCell((2, 2))));
+        assert_eq!(maze.set_wall_by_cell(2, 2, Some(maze::Direction::North), false), Err(maze::MazeError::MaskedCell((2, 2))));
+
+        // the same generators called directly leave a masked maze alone.
+        let mut masked = maze::Maze::blank(8, 5, (0, 0)).unwrap();
+        masked.set_mask(ring).unwrap();
+        let mut rng = maze::rng_from_seed(20);
+        let unsupported = |name: &str| Err(maze::MazeError::MaskNotSupported(name.to_string()));
+        assert_eq!(masked.generate_binary_maze(maze::BinaryBias::NorthEast, &mut rng), unsupported("binary"));
+        assert_eq!(masked.generate_eller(&mut rng), unsupported("eller"));
+        assert_eq!(masked.generate_recursive_division(None, &mut rng), unsupported("recursive-division"));
+        assert_eq!(masked.generate_sidewinder(&mut rng), unsupported("sidewinder"));
+        assert_eq!(masked.generate_tiled(4, &mut rng), unsupported("tiled"));
+        assert_eq!(masked.passages().count(), 0);
+    }
+
+    #[test]
+    fn test_solve() {
+        let maze = maze::Maze::generate_with_seed(15, 10, (4, 4), &maze::Kruskal, 21);
+        let path = maze.solve().unwrap();
+        assert_eq!(path.cells.first(), Some(&(4, 4)));
+        assert_eq!(path.cells.last(), Some(&maze.end()));
+        assert_eq!(path.directions.len(), maze.cost(maze.end()).unwrap());
+        assert_eq!(maze.cost((4, 4)), Some(0));
+        for (index, direction) in path.directions.iter().enumerate() {
+            let (a, b) = (path.cells[index], path.cells[index + 1]);
+            assert!(maze.has_passage(a, b));
+            assert!(maze.open_directions(a).into_iter().zip(maze.neighbors(a)).any(|(x, next)| x == *direction && next == b));
+        }
+
+        let between = maze.solve_between((9, 0), (0, 14)).unwrap().unwrap();
+        let back = maze.solve_between((0, 14), (9, 0)).unwrap().unwrap();
+        assert_eq!(between.cells.iter().rev().collect::<Vec<_>>(), back.cells.iter().collect::<Vec<_>>());
+        assert_eq!(maze.solve_between((3, 3), (3, 3)).unwrap(), Some(maze::Path { cells: vec![(3, 3)], directions: vec![] }));
+        assert!(maze.solve_between((0, 0), (10, 0)).is_err());
+        let blank = maze::Maze::blank(3, 3, (0, 0)).unwrap();
+        assert_eq!(blank.solve_between((0, 0), (2, 2)).unwrap(), None);
+    }
+
+    #[test]
+    fn test_astar() {
+        let maze = maze::Maze::generate_with_seed(20, 15, (0, 0), &maze::Prim, 22);
+        let shortest = maze.solve_between((14, 0), (0, 19)).unwrap().unwrap();
+        for heuristic in [maze::Heuristic::Manhattan, maze::Heuristic::Zero, maze::Heuristic::Custom(Box::new(|(row, _), _| row / 2))] {
+            let result = maze.solve_astar((14, 0), (0, 19), &heuristic).unwrap();
+            assert_eq!(result.path.unwrap().cells.len(), shortest.cells.len());
+            assert!(result.expanded >= shortest.cells.len() && result.expanded <= 300);
+        }
+        assert!(maze.solve_astar((0, 0), (15, 0), &maze::Heuristic::Zero).is_err());
+        // an estimate at the top of the range must not overflow the priority.
+        let huge = maze::Heuristic::Custom(Box::new(|_, _| usize::MAX));
+        assert!(maze.solve_astar((14, 0), (0, 19), &huge).unwrap().path.is_some());
+
+        // with no inner walls at all Manhattan walks straight at the goal while Dijkstra floods the grid.
+        let open = open_grid(10, 10);
+        let manhattan = open.solve_astar((0, 0), (9, 9), &maze::Heuristic::Manhattan).unwrap();
+        let dijkstra = open.solve_astar((0, 0), (9, 9), &maze::Heuristic::Zero).unwrap();
+        assert_eq!(manhattan.expanded, 19);
+        assert_eq!(dijkstra.expanded, 100);
+        assert_eq!(manhattan.path.unwrap().directions.len(), 18);
+        let closed = maze::Maze::blank(3, 3, (0, 0)).unwrap();
+        assert_eq!(closed.solve_astar((0, 0), (2, 2), &maze::Heuristic::Manhattan).unwrap(), maze::SearchResult { path: None, expanded: 1 });
+    }
+
+    #[test]
+    fn test_fill_dead_ends() {
+        let maze = maze::Maze::generate_with_seed(12, 12, (0, 0), &maze::HuntAndKill, 23);
+        let fill = maze.fill_dead_ends();
+        let mut path = maze.solve().unwrap().cells;
+        path.sort();
+        assert_eq!(fill.solution, path);
+        assert_eq!(fill.solution.len() + fill.fill_order.len(), 144);
+        // every cell is sealed only once it is a dead end among the cells still open.
+        let mut sealed = std::collections::HashSet::new();
+        for coord in fill.fill_order.iter() {
+            assert!(maze.neighbors(*coord).iter().filter(|next| !sealed.contains(*next)).count() <= 1);
+            sealed.insert(*coord);
+        }
+
+        let blank = maze::Maze::blank(2, 2, (0, 0)).unwrap();
+        assert_eq!(blank.fill_dead_ends().fill_order.len(), 3);
+    }
+
+    #[test]
+    fn test_follow_wall() {
+        let maze = maze::Maze::generate_with_seed(10, 8, (0, 0), &maze::DfsBacktracker, 24);
+        for hand in [maze::Hand::Left, maze::Hand::Right] {
+            let walk = maze.follow_wall(maze.start(), maze.end(), hand).unwrap();
+            assert!(walk.reached);
+            assert_eq!(walk.trace.first(), Some(&maze.start()));
+            assert_eq!(walk.trace.last(), Some(&maze.end()));
+            assert!(walk.trace.windows(2).all(|step| maze.has_passage(step[0], step[1])));
+        }
+
+        // a ring around the centre cell, which opens to the west. The centre's walls stand free of the outer wall:
+        // going clockwise, the left hand stays on the outer wall and circles forever, the right hand finds the way in.
+        let mut island = maze::Maze::blank(3, 3, (0, 0)).unwrap();
+        for (coord, direction) in [((0, 0), maze::Direction::East), ((0, 1), maze::Direction::East), ((0, 2), maze::Direction::South),
+                                   ((1, 2), maze::Direction::South), ((2, 2), maze::Direction::West), ((2, 1), maze::Direction::West),
+                                   ((2, 0), maze::Direction::North), ((1, 0), maze::Direction::North), ((1, 0), maze::Direction::East)] {
+            island.set_wall_by_cell(coord.0, coord.1, Some(direction), false).unwrap();
+        }
+        let walk = island.follow_wall((0, 0), (1, 1), maze::Hand::Left).unwrap();
+        assert!(!walk.reached);
+        assert_eq!(walk.trace, vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 2), (2, 1), (2, 0), (1, 0), (0, 0)]);
+        assert!(island.follow_wall((0, 0), (1, 1), maze::Hand::Right).unwrap().reached);
+        // with the centre masked out its open side counts as a wall, so the right hand no longer turns into it.
+        island.set_mask(maze::Mask::from_text("...\n.#.\n...").unwrap()).unwrap();
+        assert_eq!(island.follow_wall((1, 0), (0, 0), maze::Hand::Right).unwrap().trace, vec![(1, 0), (0, 0)]);
+        let closed = maze::Maze::blank(2, 2, (0, 0)).unwrap();
+        assert_eq!(closed.follow_wall((0, 0), (1, 1), maze::Hand::Left).unwrap(), maze::WallFollow { trace: vec![(0, 0)], reached: false });
+    }
+
+    #[test]
+    fn test_tremaux() {
+        let maze = maze::Maze::generate_with_seed(12, 9, (0, 0), &maze::Wilson, 25);
+        let walk = maze.solve_tremaux(maze.start(), maze.end()).unwrap();
+        assert!(walk.reached);
+        assert_eq!(walk.trace.last(), Some(&maze.end()));
+        assert!(walk.trace.windows(2).all(|step| maze.has_passage(step[0], step[1])));
+        assert!(walk.marks.iter().all(|(_, mark)| *mark == 1 || *mark == 2));
+        let path = maze.solve().unwrap().cells;
+        let mut expected: Vec<(maze::Coord, maze::Coord)> = path.windows(2).map(|x| (x[0].min(x[1]), x[0].max(x[1]))).collect();
+        expected.sort();
+        let marked_once: Vec<(maze::Coord, maze::Coord)> = walk.marks.iter().filter(|(_, mark)| *mark == 1).map(|(passage, _)| *passage).collect();
+        assert_eq!(marked_once, expected);
+
+        let mut corridor = maze::Maze::blank(3, 3, (0, 0)).unwrap();
+        corridor.set_wall_by_cell(0, 0, Some(maze::Direction::East), false).unwrap();
+        let walk = corridor.solve_tremaux((0, 0), (2, 2)).unwrap();
+        assert_eq!(walk, maze::TremauxWalk { trace: vec![(0, 0), (0, 1), (0, 0)], marks: vec![(((0, 0), (0, 1)), 2)], reached: false });
+    }
+
+    #[test]
+    fn test_pledge() {
+        // a bar of wall above row 2 in columns 1 to 3: the walker goes round it and heads north again.
+        let mut maze = open_grid(5, 5);
+        for col in 1..4 {
+            maze.set_wall_by_cell(2, col, Some(maze::Direction::North), true).unwrap();
+        }
+        let walk = maze.solve_pledge((4, 2), (0, 4), maze::Direction::North, maze::Hand::Left).unwrap();
+        assert!(walk.reached);
+        assert_eq!(walk.trace, vec![(4, 2), (3, 2), (2, 2), (2, 3), (2, 4), (1, 4), (0, 4)]);
+        assert_eq!(walk.turns, vec![0, 0, 0, 1, 1, 0, 0]);
+
+        // the goal sits on an island the walker can only circle.
+        let mut island = maze::Maze::blank(3, 3, (0, 0)).unwrap();
+        for (coord, direction) in [((0, 0), maze::Direction::East), ((0, 1), maze::Direction::East), ((0, 2), maze::Direction::South),
+                                   ((1, 2), maze::Direction::South), ((2, 2), maze::Direction::West), ((2, 1), maze::Direction::West),
+                                   ((2, 0), maze::Direction::North), ((1, 0), maze::Direction::North)] {
+            island.set_wall_by_cell(coord.0, coord.1, Some(direction), false).unwrap();
+        }
+        let walk = island.solve_pledge((0, 0), (1, 1), maze::Direction::South, maze::Hand::Left).unwrap();
+        assert!(!walk.reached);
+        assert_eq!(walk.trace.len(), walk.turns.len());
+        assert!(!walk.trace.contains(&(1, 1)));
+    }
+
+    #[test]
+    fn test_random_walk_on_strips() {
+        // rows and columns used to be swapped, so on a strip the walk wandered off the grid and
+        // left every interior wall standing.
+        for (width, height, direction) in [(8, 1, maze::Direction::East), (1, 8, maze::Direction::South)] {
+            let mut maze = maze::Maze::new(width, height, (0, 0));
+            for row in 0..height {
+                for col in 0..width {
+                    maze.set_wall_by_cell(row, col, Some(direction.clone()), true).unwrap();
+                }
+            }
+            maze.generate_random_walk_maze(&mut maze::rng_from_seed(8));
+            let open = (0..height)
+                .flat_map(|row| (0..width).map(move |col| (row, col)))
+                .filter(|(row, col)| {
+                    let cell = maze.get_maze_cell(*row as i32, *col as i32).unwrap();
+                    maze.get_cell_by_direction(&cell, &direction).is_some()
+                })
+                .count();
+            // a walk along a strip restarts at most once, so at most one passage is missing.
+            assert!(open >= width * height - 2, "{}x{}: only {} passages", width, height, open);
+        }
+    }
+
+    #[test]
+    fn test_bits() {
+        let mut bits = maze::Bits::new(130, false);
+        assert!((0..130).all(|index| !bits.get(index)));
+        for index in [0, 63, 64, 129] {
+            bits.set(index, true);
+        }
+        assert!((0..130).all(|index| bits.get(index) == [0, 63, 64, 129].contains(&index)));
+        bits.set(63, false);
+        assert!(!bits.get(63) && bits.get(64));
+
+        // filling leaves the bits past `len` in the last word clear, so equal bits compare equal however they
+        // were set.
+        bits.fill(true);
+        assert!((0..130).all(|index| bits.get(index)));
+        assert!((130..192).all(|index| !bits.get(index)));
+        let mut one_by_one = maze::Bits::new(130, false);
+        (0..130).for_each(|index| one_by_one.set(index, true));
+        assert_eq!(bits, one_by_one);
+        assert_eq!(bits, maze::Bits::new(130, true));
+        bits.fill(false);
+        assert_eq!(bits, maze::Bits::new(130, false));
+
+        // a last word that is completely used, and no words at all.
+        assert!((0..128).all(|index| maze::Bits::new(128, true).get(index)));
+        assert_eq!(maze::Bits::new(0, true), maze::Bits::new(0, false));
+    }
 
     #[test]
     fn test_shuffle() {
         let mut v = (0..10).collect::<Vec<usize>>();
         println!("{:?}", v);
-        v = maze::Maze::shuffle(v);
+        v = maze::Maze::shuffle(v, &mut rand::thread_rng());
         println!("{:?}", v);
     }
 }
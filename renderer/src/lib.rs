@@ -1,10 +1,15 @@
 pub mod renderer {
     use nannou::prelude::*;
-    use maze::maze::{Coord, Maze};
+    use maze::maze::{Coord, GenerationEvent, GeneratorRegistry, Maze};
+
+    // how many generation events are replayed per update when animating.
+    const STEPS_PER_UPDATE: usize = 20;
 
     struct Model {
         window: window::Id,
         maze: Option<Maze>,
+        // events still to replay into `maze` when animating.
+        steps: Option<Box<dyn Iterator<Item = GenerationEvent>>>,
     }
 
     fn model(app: &App) -> Model {
@@ -12,11 +17,34 @@ pub mod renderer {
         let width = 100;
         let height = 100;
         let start: Coord = (0, 0);
-        let maze = Maze::new(width, height, start);
-        Model { window, maze: Some(maze) }
+        // the generator can be picked by name as the first command line argument, a seed as the second,
+        // and "animate" as the third shows the maze being generated.
+        let registry = GeneratorRegistry::default();
+        let name = std::env::args().nth(1).unwrap_or_else(|| "dfs".to_string());
+        let generator = match registry.get_shared(&name) {
+            Some(generator) => generator,
+            None => {
+                println!("unknown generator {}, available: {}", name, registry.names().join(", "));
+                registry.get_shared("dfs").unwrap()
+            }
+        };
+        let seed = std::env::args().nth(2).and_then(|seed| seed.parse::<u64>().ok()).unwrap_or_else(|| {
+            std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_nanos() as u64
+        });
+        println!("{} maze, seed {}", generator.name(), seed);
+        if std::env::args().nth(3).as_deref() == Some("animate") {
+            let steps = Maze::generation_steps(width, height, start, generator, seed).unwrap();
+            Model { window, maze: Some(Maze::blank(width, height, start).unwrap()), steps: Some(Box::new(steps)) }
+        } else {
+            Model { window, maze: Some(Maze::generate_with_seed(width, height, start, generator.as_ref(), seed)), steps: None }
+        }
     }
 
-    fn update(_app: &App, model: &mut Model, _update: Update) {}
+    fn update(_app: &App, model: &mut Model, _update: Update) {
+        if let (Some(maze), Some(steps)) = (&mut model.maze, &mut model.steps) {
+            steps.by_ref().take(STEPS_PER_UPDATE).for_each(|event| maze.apply(&event).unwrap());
+        }
+    }
 
     fn view(app: &App, model: &Model, frame: Frame) {
         let draw = app.draw();
@@ -44,19 +72,17 @@ pub mod renderer {
 
     fn maze_drawer(maze: &Maze, draw: &Draw, window: std::cell::Ref<Window>, thickness: f32) {
         let (width, height) = window.inner_size_pixels();
-        let (cell_width, cell_height) = (width as f32 / maze.vertical_walls.len() as f32, height as f32 / maze.horizontal_walls.len() as f32);
+        let (cell_width, cell_height) = (width as f32 / maze.width() as f32, height as f32 / maze.height() as f32);
         let (width_offset, height_offset) = (width as f32 / 2.0, height as f32 / 2.0);
-        let (row_start, col_start) = maze.start.clone();
-        let (row_end, col_end) = maze.end.clone();
+        let (row_start, col_start) = maze.start();
+        let (row_end, col_end) = maze.end();
         draw_grid_point(col_start, row_start, GREEN, draw, cell_width, cell_height, width_offset, height_offset);
         draw_grid_point(col_end, row_end, RED, draw, cell_width, cell_height, width_offset, height_offset);
 
 
-        maze.horizontal_walls.iter().enumerate().for_each(|(row_index, walls)| {
-            walls.iter()
-                .enumerate()
-                .filter(|(_, &wall)| wall)
-                .map(|(col_index, _)| col_index)
+        (0..maze.height()).for_each(|row_index| {
+            (0..=maze.width())
+                .filter(|col_index| maze.horizontal_wall(row_index, *col_index))
                 .for_each(|col_index| {
                     // print!("<{},{}>", row_index, col_index);
 
@@ -73,11 +99,10 @@ pub mod renderer {
                         .color(YELLOW);
                 });
         });
-        maze.vertical_walls.iter().enumerate().for_each(|(row_index, walls)| {
-            walls.iter()
-                .enumerate()
-                .filter(|(_, &wall)| wall)
-                .map(|(col_index, _)| col_index)
+        // walls along the top of each cell, drawn from the left edge of column `row_index` at row `col_index`.
+        (0..maze.width()).for_each(|row_index| {
+            (0..=maze.height())
+                .filter(|col_index| maze.vertical_wall(*col_index, row_index))
                 .for_each(|col_index| {
                     // print!("<{},{}>", row_index, col_index);
                     let start_point = pt2(((row_index as f32 * cell_width) - width_offset - thickness) as f32,
@@ -100,26 +125,18 @@ pub mod renderer {
 
     fn cost_drawer(maze: &Maze, draw: &Draw, window: std::cell::Ref<Window>) {
         let (width, height) = window.inner_size_pixels();
-        let (cell_width, cell_height) = (width as f32 / maze.vertical_walls.len() as f32, height as f32 / maze.horizontal_walls.len() as f32);
+        let (cell_width, cell_height) = (width as f32 / maze.width() as f32, height as f32 / maze.height() as f32);
         let (width_offset, height_offset) = (width as f32 / 2.0, height as f32 / 2.0);
-        // println!("{:?}", maze.cost);
-        let max_value = match maze.cost.iter().map(|x| {
-            x.iter().filter(|x| x.is_some()).map(|x| x.unwrap())
-        }).flatten().max() {
-            None => 0,
+        // no heat map until the distances are known, e.g. while a generation is still being animated.
+        let max_value = match maze.max_cost() {
+            None => return,
             Some(value) => value
         };
 
-        let height = maze.cost.len();
-        let width = maze.cost[0].len();
-        (0..height).map(|row| {
-            (0..width).map(|col| {
-                (row.clone(), col.clone())
-            }).collect::<Vec<(usize, usize)>>()
-        }).collect::<Vec<Vec<(usize, usize)>>>().iter().flatten().for_each(|(row, col)| {
-            if let Some(cost) = maze.cost[*row][*col] {
+        maze.cells().for_each(|(row, col)| {
+            if let Some(cost) = maze.cost((row, col)) {
                 let grad = scale_to_u8(cost, max_value);
-                draw_grid_point(*col, *row, srgb(grad, grad, grad), draw, cell_width, cell_height, width_offset, height_offset);
+                draw_grid_point(col, row, srgb(grad, grad, grad), draw, cell_width, cell_height, width_offset, height_offset);
             }
         });
     }